@@ -1,14 +1,15 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{to_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Order, Response};
 use cw2::set_contract_version;
 use semver::Version;
 
 use crate::error::ContractError;
 use crate::helpers::validate_channel_id;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, MigrateMsg};
-use crate::state::{Config, CONFIG};
-use crate::{execute, query};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+use crate::query::HistoryPage;
+use crate::state::{Config, CONFIG, POSTERS};
+use crate::{execute, query, sudo};
 
 const CONTRACT_NAME: &str = "crates.io:milkyway-oracle";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -28,11 +29,20 @@ pub fn instantiate(
 
     let config = Config {
         admin_address: deps.api.addr_validate(&msg.admin_address)?,
+        pending_admin_address: None,
+        guardian_address: None,
+        timelock_seconds: None,
+        max_clock_skew: None,
+        max_metric_age: None,
+        max_batch_size: None,
         transfer_channel_id: msg.transfer_channel_id.clone(),
     };
 
     CONFIG.save(deps.storage, &config)?;
 
+    // The admin is also the initial poster so that the ICA can post metrics right away
+    POSTERS.save(deps.storage, &config.admin_address, &Empty {})?;
+
     Ok(Response::new()
         .add_attribute("action", "instantiate")
         .add_attribute("admin_address", msg.admin_address)
@@ -46,7 +56,7 @@ pub fn instantiate(
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
@@ -58,8 +68,10 @@ pub fn execute(
             update_time,
             block_height,
             attributes,
+            expected_latest_update_time,
         } => execute::post_metric(
             deps,
+            env,
             info,
             key,
             value,
@@ -67,48 +79,283 @@ pub fn execute(
             update_time,
             block_height,
             attributes,
+            expected_latest_update_time,
+        ),
+        ExecuteMsg::PostMetrics { metrics } => execute::post_metrics(deps, env, info, metrics),
+        ExecuteMsg::PostRedemptionRate {
+            denom,
+            redemption_rate,
+            update_time,
+            block_height,
+            key,
+            slash,
+        } => execute::post_redemption_rate(
+            deps,
+            env,
+            info,
+            denom,
+            redemption_rate,
+            update_time,
+            block_height,
+            key,
+            slash,
+        ),
+        ExecuteMsg::PostPurchaseRate {
+            denom,
+            purchase_rate,
+            update_time,
+            block_height,
+            key,
+        } => execute::post_purchase_rate(
+            deps,
+            env,
+            info,
+            denom,
+            purchase_rate,
+            update_time,
+            block_height,
+            key,
         ),
+        ExecuteMsg::UpdateConfig {
+            transfer_channel_id,
+        } => execute::update_config(deps, env, info, transfer_channel_id),
+        ExecuteMsg::ProposeAdmin { admin_address } => {
+            execute::propose_admin(deps, env, info, admin_address)
+        }
+        ExecuteMsg::AcceptAdmin {} => execute::accept_admin(deps, info),
+        ExecuteMsg::CancelAdminProposal {} => execute::cancel_admin_proposal(deps, info),
+        ExecuteMsg::GrantRole { role, address } => {
+            execute::grant_role(deps, env, info, role, address)
+        }
+        ExecuteMsg::RevokeRole { role, address } => {
+            execute::revoke_role(deps, env, info, role, address)
+        }
+        ExecuteMsg::SetPosterScope {
+            address,
+            key_prefixes,
+            metric_types,
+            denoms,
+        } => {
+            execute::set_poster_scope(deps, env, info, address, key_prefixes, metric_types, denoms)
+        }
+        ExecuteMsg::RemovePosterScope { address } => {
+            execute::remove_poster_scope(deps, env, info, address)
+        }
+        ExecuteMsg::SetTimelock { timelock_seconds } => {
+            execute::set_timelock(deps, env, info, timelock_seconds)
+        }
+        ExecuteMsg::SetRateDeviationLimit { denom, limit } => {
+            execute::set_rate_deviation_limit(deps, env, info, denom, limit)
+        }
+        ExecuteMsg::SetMonotonicRedemptionRate { denom, enabled } => {
+            execute::set_monotonic_redemption_rate(deps, env, info, denom, enabled)
+        }
+        ExecuteMsg::SetMetricBounds { target, bounds } => {
+            execute::set_metric_bounds(deps, env, info, target, bounds)
+        }
+        ExecuteMsg::SetRateMaxAge { denom, max_age } => {
+            execute::set_rate_max_age(deps, env, info, denom, max_age)
+        }
+        ExecuteMsg::SetHeartbeat { key, interval } => {
+            execute::set_heartbeat(deps, env, info, key, interval)
+        }
+        ExecuteMsg::SetMetricTimeLimits {
+            max_clock_skew,
+            max_metric_age,
+        } => execute::set_metric_time_limits(deps, env, info, max_clock_skew, max_metric_age),
+        ExecuteMsg::SetInsertPolicy { key, policy } => {
+            execute::set_insert_policy(deps, env, info, key, policy)
+        }
+        ExecuteMsg::SetMaxBatchSize { max_batch_size } => {
+            execute::set_max_batch_size(deps, env, info, max_batch_size)
+        }
+        ExecuteMsg::SetEmaHalfLife { key, half_life } => {
+            execute::set_ema_half_life(deps, env, info, key, half_life)
+        }
+        ExecuteMsg::ExecutePendingChange { id } => {
+            execute::execute_pending_change(deps, env, info, id)
+        }
+        ExecuteMsg::CancelPendingChange { id } => execute::cancel_pending_change(deps, info, id),
+        ExecuteMsg::ApprovePendingUpdate { denom } => {
+            execute::approve_pending_update(deps, info, denom)
+        }
+        ExecuteMsg::RejectPendingUpdate { denom } => {
+            execute::reject_pending_update(deps, info, denom)
+        }
+        ExecuteMsg::Pause {} => execute::pause(deps, info),
+        ExecuteMsg::Unpause {} => execute::unpause(deps, info),
+        ExecuteMsg::FreezeKey { key } => execute::freeze_key(deps, info, key),
+        ExecuteMsg::UnfreezeKey { key } => execute::unfreeze_key(deps, info, key),
+        ExecuteMsg::FreezeDenom { denom } => execute::freeze_denom(deps, info, denom),
+        ExecuteMsg::UnfreezeDenom { denom } => execute::unfreeze_denom(deps, info, denom),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
-    match msg {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    let response = match msg {
         QueryMsg::Config {} => to_binary(&CONFIG.load(deps.storage)?),
+        QueryMsg::Roles {} => to_binary(&query::get_roles(deps)?),
+        QueryMsg::PendingChanges { start_after, limit } => {
+            to_binary(&query::get_pending_changes(deps, start_after, limit)?)
+        }
+        QueryMsg::RateDeviationLimits { start_after, limit } => {
+            to_binary(&query::get_rate_deviation_limits(deps, start_after, limit)?)
+        }
+        QueryMsg::MonotonicDenoms { start_after, limit } => {
+            to_binary(&query::get_monotonic_denoms(deps, start_after, limit)?)
+        }
+        QueryMsg::MetricBounds {} => to_binary(&query::get_metric_bounds(deps)?),
+        QueryMsg::PendingUpdates { start_after, limit } => {
+            to_binary(&query::get_pending_updates(deps, start_after, limit)?)
+        }
+        QueryMsg::RateMaxAges { start_after, limit } => {
+            to_binary(&query::get_rate_max_ages(deps, start_after, limit)?)
+        }
+        QueryMsg::InsertPolicies { start_after, limit } => {
+            to_binary(&query::get_insert_policies(deps, start_after, limit)?)
+        }
+        QueryMsg::FreezeStatus {} => to_binary(&query::get_freeze_status(deps)?),
+        QueryMsg::PosterScopes { start_after, limit } => {
+            to_binary(&query::get_poster_scopes(deps, start_after, limit)?)
+        }
+        QueryMsg::Health {} => to_binary(&query::get_health(deps, env)?),
+        QueryMsg::SimulatePostMetric { sender, metric } => {
+            to_binary(&query::simulate_post_metric(deps, env, sender, metric)?)
+        }
         QueryMsg::Metric { key } => to_binary(&query::get_latest_metric(deps, key)?),
-        QueryMsg::HistoricalMetrics { key, limit } => {
-            to_binary(&query::get_historical_metrics(deps, key, limit)?)
+        QueryMsg::Ema { key } => to_binary(&query::get_ema(deps, key)?),
+        QueryMsg::HistoricalMetrics {
+            key,
+            start_time,
+            end_time,
+            start_after,
+            order,
+            limit,
+        } => {
+            let page = HistoryPage {
+                start_time,
+                end_time,
+                start_after,
+                order,
+                limit,
+            };
+            to_binary(&query::get_historical_metrics(deps, key, page)?)
         }
+        QueryMsg::MetricAt {
+            key,
+            time,
+            interpolate,
+        } => to_binary(&query::get_metric_at(deps, key, time, interpolate)?),
         QueryMsg::AllLatestMetrics {} => to_binary(&query::get_all_latest_metrics(deps)?),
-        QueryMsg::RedemptionRate { denom, params, .. } => {
-            to_binary(&query::get_latest_redemption_rate(deps, denom, params)?)
+        QueryMsg::MetricStats { key, since, until } => {
+            to_binary(&query::get_metric_stats(deps, key, since, until)?)
         }
+        QueryMsg::RedemptionRate { denom, params, .. } => to_binary(
+            &query::get_latest_redemption_rate(deps, env, denom, params)?,
+        ),
         QueryMsg::HistoricalRedemptionRates {
             denom,
             params,
+            start_time,
+            end_time,
+            start_after,
+            order,
             limit,
-            ..
-        } => to_binary(&query::get_historical_redemption_rates(
-            deps, denom, params, limit,
+        } => {
+            let page = HistoryPage {
+                start_time,
+                end_time,
+                start_after,
+                order,
+                limit,
+            };
+            to_binary(&query::get_historical_redemption_rates(
+                deps, denom, params, page,
+            )?)
+        }
+        QueryMsg::RedemptionRateAt {
+            denom,
+            time,
+            interpolate,
+        } => to_binary(&query::get_redemption_rate_at(
+            deps,
+            denom,
+            time,
+            interpolate,
         )?),
         QueryMsg::PurchaseRate { denom, params, .. } => {
-            to_binary(&query::get_latest_purchase_rate(deps, denom, params)?)
+            to_binary(&query::get_latest_purchase_rate(deps, env, denom, params)?)
         }
         QueryMsg::HistoricalPurchaseRates {
             denom,
             params,
+            start_time,
+            end_time,
+            start_after,
+            order,
             limit,
-            ..
-        } => to_binary(&query::get_historical_purchase_rates(
-            deps, denom, params, limit,
+        } => {
+            let page = HistoryPage {
+                start_time,
+                end_time,
+                start_after,
+                order,
+                limit,
+            };
+            to_binary(&query::get_historical_purchase_rates(
+                deps, denom, params, page,
+            )?)
+        }
+        QueryMsg::PurchaseRateAt {
+            denom,
+            time,
+            interpolate,
+        } => to_binary(&query::get_purchase_rate_at(
+            deps,
+            denom,
+            time,
+            interpolate,
         )?),
+        QueryMsg::SlashEvents {
+            denom,
+            start_time,
+            end_time,
+            start_after,
+            order,
+            limit,
+        } => {
+            let page = HistoryPage {
+                start_time,
+                end_time,
+                start_after,
+                order,
+                limit,
+            };
+            to_binary(&query::get_slash_events(deps, denom, page)?)
+        }
+    }?;
+    Ok(response)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, _env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateAdmin { admin_address } => sudo::update_admin(deps, admin_address),
+        SudoMsg::Pause {} => sudo::pause(deps),
+        SudoMsg::Unpause {} => sudo::unpause(deps),
+        SudoMsg::RemoveMetric { key, update_time } => sudo::remove_metric(deps, key, update_time),
+        SudoMsg::UpdateConfig {
+            transfer_channel_id,
+        } => sudo::update_config(deps, transfer_channel_id),
+        SudoMsg::SetTimelock { timelock_seconds } => sudo::set_timelock(deps, timelock_seconds),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
     let current_version = cw2::get_contract_version(deps.storage)?;
-    if &CONTRACT_NAME != &current_version.contract.as_str() {
+    if CONTRACT_NAME != current_version.contract.as_str() {
         return Err(ContractError::InvalidContract {});
     }
 
@@ -130,7 +377,16 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
     }
 
     // migrate data
-    // none
+    // Contracts deployed before the poster role was introduced only authorized the admin
+    // to post metrics, so the admin is granted the poster role if no posters are set
+    let no_posters = POSTERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    if no_posters {
+        let config = CONFIG.load(deps.storage)?;
+        POSTERS.save(deps.storage, &config.admin_address, &Empty {})?;
+    }
 
     // set new contract version
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -142,19 +398,32 @@ pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, C
 mod tests {
     use std::str::FromStr;
 
-    use crate::contract::{execute, instantiate, query};
+    use crate::contract::{execute, instantiate, migrate, query, sudo, CONTRACT_NAME};
     use cosmwasm_std::testing::{
         mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
     };
     use cosmwasm_std::{
-        attr, from_binary, to_binary, Addr, Decimal, Empty, Env, MessageInfo, OwnedDeps,
+        attr, from_binary, to_binary, Addr, Decimal, Empty, Env, Event, MessageInfo, OwnedDeps,
     };
 
     use crate::error::ContractError;
     use crate::msg::{
-        ExecuteMsg, InstantiateMsg, Metrics, QueryMsg, RedemptionRateResponse, RedemptionRates, PurchaseRateResponse, PurchaseRates
+        EmaResponse, ExecuteMsg, FreezeStatusResponse, HealthResponse, HealthStatus, HistoryOrder,
+        InsertPoliciesResponse, InsertPolicyEntry, InstantiateMsg, KeyHealth, MetricBoundsEntry,
+        MetricBoundsResponse, MetricOutcome, MetricStatsResponse, MetricUpdate, Metrics,
+        MigrateMsg, MonotonicDenomsResponse, PendingChangesResponse, PendingUpdatesResponse,
+        PostMetricResponse, PostMetricsResponse, PosterScopeEntry, PosterScopesResponse,
+        PurchaseRateResponse, PurchaseRates, QueryMsg, RateDeviationLimitEntry,
+        RateDeviationLimitsResponse, RateMaxAgeEntry, RateMaxAgesResponse, RateOutcome,
+        RateQueryParams, RedemptionRateResponse, RedemptionRates, RolesResponse,
+        SimulatePostMetricResponse, SlashEvents, SudoMsg,
+    };
+    use crate::state::{
+        BoundsTarget, Config, ConfigChange, DeviationAction, DuplicatePolicy, InsertOrdering,
+        InsertPolicy, Metric, MetricBounds, MetricType, PendingChange, PendingRateUpdate,
+        PosterScope, PurchaseRate, PurchaseRateAttributes, RateDeviationLimit, RateUpdate,
+        RedemptionRate, RedemptionRateAttributes, Role, SlashEvent, POSTERS,
     };
-    use crate::state::{Config, Metric, MetricType, RedemptionRate, RedemptionRateAttributes, PurchaseRate, PurchaseRateAttributes};
 
     const ADMIN_ADDRESS: &str = "admin";
     const TRANSFER_CHANNEL_ID: &str = "channel-0";
@@ -202,10 +471,12 @@ mod tests {
 
     // Helper function to build a redemption rate object
     // The time field is used for both the time and the block_height
+    // The received time and height are those of the mock env
     // It uses a generic denom and ibc/denom
     fn get_test_redemption_rate_metric(key: &str, value: &str, time: u64) -> Metric {
         let redemption_rate_attributes = RedemptionRateAttributes {
             sttoken_denom: STTOKEN_DENOM.to_string(),
+            slash: false,
         };
         let redemption_rate_attributes = Some(to_binary(&redemption_rate_attributes).unwrap());
 
@@ -216,11 +487,14 @@ mod tests {
             update_time: time,
             block_height: time,
             attributes: redemption_rate_attributes,
+            received_time: Some(mock_env().block.time.seconds()),
+            received_height: Some(mock_env().block.height),
         }
     }
 
     // Helper function to build a redemption rate object
     // The time field is used for both the time and the block_height
+    // The received time and height are those of the mock env
     // It uses a generic denom and ibc/denom
     fn get_test_purchase_rate_metric(key: &str, value: &str, time: u64) -> Metric {
         let purchase_rate_attributes = PurchaseRateAttributes {
@@ -235,6 +509,8 @@ mod tests {
             update_time: time,
             block_height: time,
             attributes: purchase_rate_attributes,
+            received_time: Some(mock_env().block.time.seconds()),
+            received_height: Some(mock_env().block.height),
         }
     }
 
@@ -244,6 +520,8 @@ mod tests {
             denom: STTOKEN_DENOM.to_string(),
             redemption_rate: Decimal::from_str(value).unwrap(),
             update_time: time,
+            received_time: Some(mock_env().block.time.seconds()),
+            received_height: Some(mock_env().block.height),
         }
     }
 
@@ -253,6 +531,8 @@ mod tests {
             denom: STTOKEN_DENOM.to_string(),
             purchase_rate: Decimal::from_str(value).unwrap(),
             update_time: time,
+            received_time: Some(mock_env().block.time.seconds()),
+            received_height: Some(mock_env().block.height),
         }
     }
 
@@ -262,9 +542,23 @@ mod tests {
             key: metric.key.clone(),
             value: metric.value.clone(),
             metric_type: metric.metric_type.clone(),
-            update_time: metric.update_time.clone(),
-            block_height: metric.block_height.clone(),
+            update_time: metric.update_time,
+            block_height: metric.block_height,
+            attributes: metric.attributes.clone(),
+            expected_latest_update_time: None,
+        }
+    }
+
+    // Helper function to build a metric within a PostMetrics batch, given a metric
+    fn get_metric_update(metric: &Metric) -> MetricUpdate {
+        MetricUpdate {
+            key: metric.key.clone(),
+            value: metric.value.clone(),
+            metric_type: metric.metric_type.clone(),
+            update_time: metric.update_time,
+            block_height: metric.block_height,
             attributes: metric.attributes.clone(),
+            expected_latest_update_time: None,
         }
     }
 
@@ -280,6 +574,12 @@ mod tests {
             config,
             Config {
                 admin_address: Addr::unchecked(ADMIN_ADDRESS.to_string()),
+                pending_admin_address: None,
+                guardian_address: None,
+                timelock_seconds: None,
+                max_clock_skew: None,
+                max_metric_age: None,
+                max_batch_size: None,
                 transfer_channel_id: Some(TRANSFER_CHANNEL_ID.to_string()),
             }
         )
@@ -312,6 +612,12 @@ mod tests {
             config,
             Config {
                 admin_address: Addr::unchecked(ADMIN_ADDRESS.to_string()),
+                pending_admin_address: None,
+                guardian_address: None,
+                timelock_seconds: None,
+                max_clock_skew: None,
+                max_metric_age: None,
+                max_batch_size: None,
                 transfer_channel_id: None,
             }
         )
@@ -357,8 +663,18 @@ mod tests {
                 attr("metric_update_time", "1"),
                 attr("metric_block_height", "1"),
                 attr("metric_attributes", attributes_string),
+                attr("metric_outcome", "inserted"),
+                attr("rate_outcome", "inserted"),
             ]
         );
+        let data: PostMetricResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Inserted,
+                rate_outcome: Some(RateOutcome::Inserted),
+            }
+        );
 
         // Confirm the metric is present
         let query_latest_msg = QueryMsg::Metric {
@@ -374,14 +690,16 @@ mod tests {
             denom: STTOKEN_DENOM.to_string(),
             params: None,
         };
-        let resp = query(deps.as_ref(), env, query_redemption_rate_msg).unwrap();
+        let resp = query(deps.as_ref(), env.clone(), query_redemption_rate_msg).unwrap();
         let redemption_rate_response: RedemptionRateResponse = from_binary(&resp).unwrap();
         let expected_redemption_rate = Decimal::one();
         assert_eq!(
             redemption_rate_response,
             RedemptionRateResponse {
                 redemption_rate: expected_redemption_rate,
-                update_time: 1
+                update_time: 1,
+                received_time: Some(env.block.time.seconds()),
+                received_height: Some(env.block.height),
             }
         );
     }
@@ -407,8 +725,18 @@ mod tests {
                 attr("metric_update_time", "1"),
                 attr("metric_block_height", "1"),
                 attr("metric_attributes", attributes_string),
+                attr("metric_outcome", "inserted"),
+                attr("rate_outcome", "inserted"),
             ]
         );
+        let data: PostMetricResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(
+            data,
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Inserted,
+                rate_outcome: Some(RateOutcome::Inserted),
+            }
+        );
 
         // Confirm the metric is present
         let query_latest_msg = QueryMsg::Metric {
@@ -431,7 +759,9 @@ mod tests {
             purchase_rate_response,
             PurchaseRateResponse {
                 purchase_rate: expected_purchase_rate,
-                update_time: 1
+                update_time: 1,
+                received_time: Some(env.block.time.seconds()),
+                received_height: Some(env.block.height),
             }
         );
     }
@@ -471,6 +801,10 @@ mod tests {
         // Confirm metrics 1, 2 and 4 are preset and are sorted
         let msg = QueryMsg::HistoricalMetrics {
             key: metric_key.to_string(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: None,
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -485,6 +819,10 @@ mod tests {
         // Check querying metrics with a limit
         let msg = QueryMsg::HistoricalMetrics {
             key: metric_key.to_string(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: Some(2),
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -500,6 +838,10 @@ mod tests {
         // Confirm metrics 1, 2 and 4 are preset and are sorted
         let msg = QueryMsg::HistoricalMetrics {
             key: metric_key2.to_string(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: None,
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -514,6 +856,10 @@ mod tests {
         // Check querying metrics with a limit
         let msg = QueryMsg::HistoricalMetrics {
             key: metric_key2.to_string(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: Some(2),
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -529,6 +875,10 @@ mod tests {
         let msg = QueryMsg::HistoricalRedemptionRates {
             denom: rr1.denom.clone(),
             params: None,
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: None,
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -544,6 +894,10 @@ mod tests {
         let msg = QueryMsg::HistoricalRedemptionRates {
             denom: rr1.denom.clone(),
             params: None,
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: Some(2),
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -559,6 +913,10 @@ mod tests {
         let msg = QueryMsg::HistoricalPurchaseRates {
             denom: pr1.denom.clone(),
             params: None,
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: None,
         };
         let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
@@ -574,6 +932,10 @@ mod tests {
         let msg = QueryMsg::HistoricalPurchaseRates {
             denom: pr1.denom.clone(),
             params: None,
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
             limit: Some(2),
         };
         let resp = query(deps.as_ref(), env, msg).unwrap();
@@ -722,4 +1084,2901 @@ mod tests {
         let latest_response: Metric = from_binary(&resp).unwrap();
         assert_eq!(latest_response, valid_metric);
     }
+
+    #[test]
+    fn test_update_config() {
+        // Instantiate contract
+        let (mut deps, env, info) = default_instantiate();
+
+        // A non-admin should not be able to update the config
+        let msg = ExecuteMsg::UpdateConfig {
+            transfer_channel_id: Some("channel-1".to_string()),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), mock_info("not_admin", &[]), msg);
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        // An invalid channel ID should be rejected
+        let msg = ExecuteMsg::UpdateConfig {
+            transfer_channel_id: Some("chan-1".to_string()),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(
+            resp,
+            Err(ContractError::InvalidChannelID {
+                channel_id: "chan-1".to_string()
+            })
+        );
+
+        // The admin should be able to update the channel ID
+        let msg = ExecuteMsg::UpdateConfig {
+            transfer_channel_id: Some("channel-1".to_string()),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            resp.attributes,
+            vec![
+                attr("action", "update_config"),
+                attr("transfer_channel_id", "channel-1"),
+            ]
+        );
+
+        let resp = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.transfer_channel_id, Some("channel-1".to_string()));
+    }
+
+    #[test]
+    fn test_admin_handover() {
+        // Instantiate contract
+        let (mut deps, env, info) = default_instantiate();
+        let new_admin = "new_admin";
+        let new_admin_info = mock_info(new_admin, &[]);
+
+        // Accepting without a proposal should fail
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            new_admin_info.clone(),
+            ExecuteMsg::AcceptAdmin {},
+        );
+        assert_eq!(resp, Err(ContractError::NoPendingAdmin {}));
+
+        // Only the current admin can propose a new admin
+        let propose_msg = ExecuteMsg::ProposeAdmin {
+            admin_address: new_admin.to_string(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            new_admin_info.clone(),
+            propose_msg.clone(),
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            propose_msg.clone(),
+        )
+        .unwrap();
+
+        // The pending admin should be visible through the config query
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.admin_address, Addr::unchecked(ADMIN_ADDRESS));
+        assert_eq!(
+            config.pending_admin_address,
+            Some(Addr::unchecked(new_admin))
+        );
+
+        // The proposal can be cancelled by the current admin
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::CancelAdminProposal {},
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            new_admin_info.clone(),
+            ExecuteMsg::AcceptAdmin {},
+        );
+        assert_eq!(resp, Err(ContractError::NoPendingAdmin {}));
+
+        // Propose again, and confirm only the pending admin can accept
+        execute(deps.as_mut(), env.clone(), info.clone(), propose_msg).unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("not_admin", &[]),
+            ExecuteMsg::AcceptAdmin {},
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            new_admin_info.clone(),
+            ExecuteMsg::AcceptAdmin {},
+        )
+        .unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.admin_address, Addr::unchecked(new_admin));
+        assert_eq!(config.pending_admin_address, None);
+
+        // The old admin should no longer be able to update the config
+        let msg = ExecuteMsg::UpdateConfig {
+            transfer_channel_id: None,
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info, msg.clone());
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+        execute(deps.as_mut(), env, new_admin_info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_grant_and_revoke_roles() {
+        // Instantiate contract
+        let (mut deps, env, info) = default_instantiate();
+        let poster = "poster";
+        let guardian = "guardian";
+
+        // The admin should be the only poster after instantiation
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Roles {}).unwrap();
+        let roles: RolesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            roles,
+            RolesResponse {
+                admin_address: Addr::unchecked(ADMIN_ADDRESS),
+                pending_admin_address: None,
+                guardian_address: None,
+                posters: vec![Addr::unchecked(ADMIN_ADDRESS)],
+            }
+        );
+
+        // Only the admin can grant roles
+        let grant_poster_msg = ExecuteMsg::GrantRole {
+            role: Role::Poster,
+            address: poster.to_string(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(poster, &[]),
+            grant_poster_msg.clone(),
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        // Grant the poster and guardian roles
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), grant_poster_msg).unwrap();
+        assert_eq!(
+            resp.attributes,
+            vec![
+                attr("action", "grant_role"),
+                attr("role", "poster"),
+                attr("address", poster),
+            ]
+        );
+        let grant_guardian_msg = ExecuteMsg::GrantRole {
+            role: Role::Guardian,
+            address: guardian.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), grant_guardian_msg).unwrap();
+
+        // Revoke the poster role from the admin so that only the new poster remains
+        let revoke_admin_msg = ExecuteMsg::RevokeRole {
+            role: Role::Poster,
+            address: ADMIN_ADDRESS.to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            revoke_admin_msg.clone(),
+        )
+        .unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Roles {}).unwrap();
+        let roles: RolesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            roles,
+            RolesResponse {
+                admin_address: Addr::unchecked(ADMIN_ADDRESS),
+                pending_admin_address: None,
+                guardian_address: Some(Addr::unchecked(guardian)),
+                posters: vec![Addr::unchecked(poster)],
+            }
+        );
+
+        // Revoking a role that isn't held should fail
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), revoke_admin_msg);
+        assert_eq!(
+            resp,
+            Err(ContractError::RoleNotFound {
+                role: Role::Poster,
+                address: ADMIN_ADDRESS.to_string(),
+            })
+        );
+        let revoke_guardian_msg = ExecuteMsg::RevokeRole {
+            role: Role::Guardian,
+            address: poster.to_string(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            revoke_guardian_msg,
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::RoleNotFound {
+                role: Role::Guardian,
+                address: poster.to_string(),
+            })
+        );
+
+        // The admin can no longer post, but the poster can
+        let metric = get_test_redemption_rate_metric("key1", "1", 1);
+        let post_msg = get_post_metric_msg(&metric);
+        let resp = execute(deps.as_mut(), env.clone(), info, post_msg.clone());
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        // Neither can the guardian
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(guardian, &[]),
+            post_msg.clone(),
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        execute(deps.as_mut(), env, mock_info(poster, &[]), post_msg).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_grants_admin_poster_role() {
+        // Instantiate contract and then remove the posters to mimic a contract
+        // that was deployed before roles were introduced
+        let (mut deps, env, _) = default_instantiate();
+        POSTERS.remove(deps.as_mut().storage, &Addr::unchecked(ADMIN_ADDRESS));
+        cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "1.0.0").unwrap();
+
+        migrate(deps.as_mut(), env.clone(), MigrateMsg {}).unwrap();
+
+        let resp = query(deps.as_ref(), env, QueryMsg::Roles {}).unwrap();
+        let roles: RolesResponse = from_binary(&resp).unwrap();
+        assert_eq!(roles.posters, vec![Addr::unchecked(ADMIN_ADDRESS)]);
+    }
+
+    #[test]
+    fn test_poster_scopes() {
+        // Instantiate contract and add two posters
+        let (mut deps, env, info) = default_instantiate();
+        let stride = "stride";
+        let milkyway = "milkyway";
+        for poster in [stride, milkyway] {
+            let msg = ExecuteMsg::GrantRole {
+                role: Role::Poster,
+                address: poster.to_string(),
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // Scope stride to st-token redemption rates and milkyway to milk-token keys and denoms
+        let stride_scope = PosterScope {
+            key_prefixes: vec!["st".to_string()],
+            metric_types: vec![MetricType::RedemptionRate],
+            denoms: vec![],
+        };
+        let milkyway_scope = PosterScope {
+            key_prefixes: vec!["milk".to_string()],
+            metric_types: vec![],
+            denoms: vec!["milktia".to_string()],
+        };
+        for (poster, scope) in [(stride, &stride_scope), (milkyway, &milkyway_scope)] {
+            let msg = ExecuteMsg::SetPosterScope {
+                address: poster.to_string(),
+                key_prefixes: scope.key_prefixes.clone(),
+                metric_types: scope.metric_types.clone(),
+                denoms: scope.denoms.clone(),
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // Only the admin can set scopes
+        let msg = ExecuteMsg::SetPosterScope {
+            address: stride.to_string(),
+            key_prefixes: vec![],
+            metric_types: vec![],
+            denoms: vec![],
+        };
+        let resp = execute(deps.as_mut(), env.clone(), mock_info(stride, &[]), msg);
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        // Stride can post st-token redemption rates, but not other types or keys
+        let st_metric = get_test_redemption_rate_metric("stuatom_redemption_rate", "1", 1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(stride, &[]),
+            get_post_metric_msg(&st_metric),
+        )
+        .unwrap();
+
+        let st_purchase_metric = get_test_purchase_rate_metric("stuatom_purchase_rate", "1", 1);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(stride, &[]),
+            get_post_metric_msg(&st_purchase_metric),
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::PosterScopeViolation {
+                poster: stride.to_string(),
+                key: "stuatom_purchase_rate".to_string(),
+                metric_type: MetricType::PurchaseRate,
+            })
+        );
+
+        let mut milk_metric = get_test_purchase_rate_metric("milktia_purchase_rate", "1", 1);
+        milk_metric.attributes = Some(
+            to_binary(&PurchaseRateAttributes {
+                sttoken_denom: "milktia".to_string(),
+            })
+            .unwrap(),
+        );
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(stride, &[]),
+            get_post_metric_msg(&milk_metric),
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::PosterScopeViolation {
+                poster: stride.to_string(),
+                key: "milktia_purchase_rate".to_string(),
+                metric_type: MetricType::PurchaseRate,
+            })
+        );
+
+        // MilkyWay can post any type under its prefix, but can't overwrite stride's metric
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(milkyway, &[]),
+            get_post_metric_msg(&milk_metric),
+        )
+        .unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(milkyway, &[]),
+            get_post_metric_msg(&st_metric),
+        );
+        assert!(matches!(
+            resp,
+            Err(ContractError::PosterScopeViolation { .. })
+        ));
+
+        // MilkyWay also can't post a rate for stride's denom under one of its own keys
+        let mut milk_key_metric = st_metric.clone();
+        milk_key_metric.key = "milk_stuatom_redemption_rate".to_string();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info(milkyway, &[]),
+            get_post_metric_msg(&milk_key_metric),
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::PosterDenomViolation {
+                poster: milkyway.to_string(),
+                denom: STTOKEN_DENOM.to_string(),
+            })
+        );
+
+        // The admin has no scope and can post anything
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&st_purchase_metric),
+        )
+        .unwrap();
+
+        // Check the paginated scopes query
+        let msg = QueryMsg::PosterScopes {
+            start_after: None,
+            limit: Some(1),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let scopes: PosterScopesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            scopes,
+            PosterScopesResponse {
+                scopes: vec![PosterScopeEntry {
+                    address: Addr::unchecked(milkyway),
+                    scope: milkyway_scope,
+                }]
+            }
+        );
+
+        let msg = QueryMsg::PosterScopes {
+            start_after: Some(milkyway.to_string()),
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let scopes: PosterScopesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            scopes,
+            PosterScopesResponse {
+                scopes: vec![PosterScopeEntry {
+                    address: Addr::unchecked(stride),
+                    scope: stride_scope,
+                }]
+            }
+        );
+
+        // Once the scope is removed, stride can post any metric
+        let msg = ExecuteMsg::RemovePosterScope {
+            address: stride.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info(stride, &[]),
+            get_post_metric_msg(&st_purchase_metric),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_pause_and_freeze() {
+        // Instantiate contract, add a guardian, and post a redemption rate
+        let (mut deps, env, info) = default_instantiate();
+        let guardian_info = mock_info("guardian", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            role: Role::Guardian,
+            address: "guardian".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let metric = get_test_redemption_rate_metric("key1", "1", 1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+
+        let rate_query = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+
+        // Only the admin or guardian can pause
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("not_admin", &[]),
+            ExecuteMsg::Pause {},
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            guardian_info.clone(),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+
+        // While paused, posts and rate queries should fail
+        let metric = get_test_redemption_rate_metric("key1", "2", 2);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        );
+        assert_eq!(resp, Err(ContractError::Paused {}));
+        let resp = query(deps.as_ref(), env.clone(), rate_query.clone());
+        assert_eq!(resp, Err(ContractError::Paused {}));
+
+        // The guardian cannot unpause, but the admin can
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            guardian_info.clone(),
+            ExecuteMsg::Unpause {},
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+        query(deps.as_ref(), env.clone(), rate_query.clone()).unwrap();
+
+        // Freeze the key and denom
+        let msg = ExecuteMsg::FreezeKey {
+            key: "key1".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), guardian_info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::FreezeDenom {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), guardian_info, msg).unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::FreezeStatus {}).unwrap();
+        let status: FreezeStatusResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            status,
+            FreezeStatusResponse {
+                paused: false,
+                frozen_keys: vec!["key1".to_string()],
+                frozen_denoms: vec![STTOKEN_DENOM.to_string()],
+            }
+        );
+
+        // Posting to the frozen key should fail
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::KeyFrozen {
+                key: "key1".to_string()
+            })
+        );
+
+        // Posting to an unfrozen key for the frozen denom should also fail
+        let metric = get_test_redemption_rate_metric("key2", "2", 2);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::DenomFrozen {
+                denom: STTOKEN_DENOM.to_string()
+            })
+        );
+
+        // And the rate query should fail closed
+        let resp = query(deps.as_ref(), env.clone(), rate_query.clone());
+        assert_eq!(
+            resp,
+            Err(ContractError::DenomFrozen {
+                denom: STTOKEN_DENOM.to_string()
+            })
+        );
+
+        // Once unfrozen, everything should work again
+        let msg = ExecuteMsg::UnfreezeDenom {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::UnfreezeKey {
+            key: "key1".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let metric = get_test_redemption_rate_metric("key1", "2", 2);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+        let resp = query(deps.as_ref(), env, rate_query).unwrap();
+        let rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(rate.redemption_rate, Decimal::from_str("2").unwrap());
+    }
+
+    #[test]
+    fn test_sudo() {
+        // Instantiate contract and post a few redemption rates
+        let (mut deps, env, info) = default_instantiate();
+        let metric1 = get_test_redemption_rate_metric("key1", "1", 1);
+        let metric2 = get_test_redemption_rate_metric("key1", "2", 2);
+        let metric3 = get_test_redemption_rate_metric("key1", "3", 3);
+        for metric in [&metric1, &metric2, &metric3] {
+            let msg = get_post_metric_msg(metric);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        // Replace the admin through governance
+        let msg = SudoMsg::UpdateAdmin {
+            admin_address: "gov_admin".to_string(),
+        };
+        sudo(deps.as_mut(), env.clone(), msg).unwrap();
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.admin_address, Addr::unchecked("gov_admin"));
+
+        // Update the config through governance
+        let msg = SudoMsg::UpdateConfig {
+            transfer_channel_id: Some("channel-5".to_string()),
+        };
+        sudo(deps.as_mut(), env.clone(), msg).unwrap();
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.transfer_channel_id, Some("channel-5".to_string()));
+
+        // Pause and unpause through governance
+        sudo(deps.as_mut(), env.clone(), SudoMsg::Pause {}).unwrap();
+        let msg = get_post_metric_msg(&get_test_redemption_rate_metric("key1", "4", 4));
+        let resp = execute(deps.as_mut(), env.clone(), info, msg);
+        assert_eq!(resp, Err(ContractError::Paused {}));
+        sudo(deps.as_mut(), env.clone(), SudoMsg::Unpause {}).unwrap();
+
+        // Remove the latest metric, which should also remove the latest redemption rate
+        let msg = SudoMsg::RemoveMetric {
+            key: "key1".to_string(),
+            update_time: Some(3),
+        };
+        sudo(deps.as_mut(), env.clone(), msg).unwrap();
+
+        let msg = QueryMsg::HistoricalMetrics {
+            key: "key1".to_string(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let metrics: Metrics = from_binary(&resp).unwrap();
+        assert_eq!(metrics.metrics, vec![metric2, metric1]);
+
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(rate.update_time, 2);
+
+        // Removing a metric that doesn't exist should fail
+        let msg = SudoMsg::RemoveMetric {
+            key: "key1".to_string(),
+            update_time: Some(3),
+        };
+        let resp = sudo(deps.as_mut(), env.clone(), msg);
+        assert_eq!(
+            resp,
+            Err(ContractError::MetricNotFound {
+                key: "key1".to_string()
+            })
+        );
+
+        // Remove the full history of the key
+        let msg = SudoMsg::RemoveMetric {
+            key: "key1".to_string(),
+            update_time: None,
+        };
+        sudo(deps.as_mut(), env.clone(), msg).unwrap();
+
+        let msg = QueryMsg::AllLatestMetrics {};
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let metrics: Metrics = from_binary(&resp).unwrap();
+        assert_eq!(metrics.metrics, vec![]);
+
+        let msg = QueryMsg::HistoricalRedemptionRates {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env, msg).unwrap();
+        let rates: RedemptionRates = from_binary(&resp).unwrap();
+        assert_eq!(rates.redemption_rates, vec![]);
+    }
+
+    #[test]
+    fn test_timelock() {
+        // Instantiate contract with a guardian, and then enable the timelock
+        // Since there's no timelock yet, these should take effect immediately
+        let (mut deps, mut env, info) = default_instantiate();
+        let guardian_info = mock_info("guardian", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            role: Role::Guardian,
+            address: "guardian".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetTimelock {
+            timelock_seconds: Some(100),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Granting a role should now be queued rather than applied
+        let start_time = env.block.time.seconds();
+        let grant_msg = ExecuteMsg::GrantRole {
+            role: Role::Poster,
+            address: "poster".to_string(),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), grant_msg.clone()).unwrap();
+        assert_eq!(
+            resp.attributes,
+            vec![
+                attr("action", "queue_config_change"),
+                attr("change_id", "1"),
+                attr("change", "grant_role"),
+                attr("execute_after", (start_time + 100).to_string()),
+            ]
+        );
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Roles {}).unwrap();
+        let roles: RolesResponse = from_binary(&resp).unwrap();
+        assert_eq!(roles.posters, vec![Addr::unchecked(ADMIN_ADDRESS)]);
+
+        let msg = QueryMsg::PendingChanges {
+            start_after: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg.clone()).unwrap();
+        let pending: PendingChangesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            pending.changes,
+            vec![PendingChange {
+                id: 1,
+                change: ConfigChange::GrantRole {
+                    role: Role::Poster,
+                    address: Addr::unchecked("poster"),
+                },
+                execute_after: start_time + 100,
+            }]
+        );
+
+        // The guardian can cancel the change during the delay
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("not_admin", &[]),
+            ExecuteMsg::CancelPendingChange { id: 1 },
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            guardian_info,
+            ExecuteMsg::CancelPendingChange { id: 1 },
+        )
+        .unwrap();
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ExecutePendingChange { id: 1 },
+        );
+        assert_eq!(resp, Err(ContractError::PendingChangeNotFound { id: 1 }));
+
+        // Queue the change again, it should not be executable before the delay
+        execute(deps.as_mut(), env.clone(), info.clone(), grant_msg).unwrap();
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ExecutePendingChange { id: 2 },
+        );
+        assert_eq!(
+            resp,
+            Err(ContractError::TimelockNotExpired {
+                id: 2,
+                execute_after: start_time + 100
+            })
+        );
+
+        // Once the delay has passed, the admin can execute it
+        env.block.time = env.block.time.plus_seconds(100);
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("poster", &[]),
+            ExecuteMsg::ExecutePendingChange { id: 2 },
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ExecutePendingChange { id: 2 },
+        )
+        .unwrap();
+        assert_eq!(
+            resp.attributes,
+            vec![
+                attr("action", "grant_role"),
+                attr("role", "poster"),
+                attr("address", "poster"),
+                attr("change_id", "2"),
+            ]
+        );
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Roles {}).unwrap();
+        let roles: RolesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            roles.posters,
+            vec![Addr::unchecked(ADMIN_ADDRESS), Addr::unchecked("poster")]
+        );
+
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let pending: PendingChangesResponse = from_binary(&resp).unwrap();
+        assert_eq!(pending.changes, vec![]);
+
+        // A timelock longer than the max should be rejected, since it would overflow
+        // the execution time of queued changes
+        let msg = ExecuteMsg::SetTimelock {
+            timelock_seconds: Some(u64::MAX),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info, msg);
+        assert_eq!(
+            resp,
+            Err(ContractError::TimelockTooLong {
+                timelock_seconds: u64::MAX,
+                max_timelock_seconds: 30 * 24 * 60 * 60,
+            })
+        );
+
+        // Governance can remove the timelock directly, without waiting for the delay
+        let msg = SudoMsg::SetTimelock {
+            timelock_seconds: None,
+        };
+        sudo(deps.as_mut(), env.clone(), msg).unwrap();
+
+        let resp = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.timelock_seconds, None);
+    }
+
+    #[test]
+    fn test_rate_deviation_limit() {
+        // Instantiate contract and post an initial redemption rate
+        let (mut deps, env, info) = default_instantiate();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    value: &str,
+                    time: u64| {
+            let metric = get_test_redemption_rate_metric("key1", value, time);
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(&metric),
+            )
+        };
+        let latest_rate = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            let msg = QueryMsg::RedemptionRate {
+                denom: STTOKEN_DENOM.to_string(),
+                params: None,
+            };
+            let resp = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<RedemptionRateResponse>(&resp).unwrap()
+        };
+        post(&mut deps, "1.0", 1).unwrap();
+
+        // Limit the redemption rate to a 10% move per update
+        let mut limit = RateDeviationLimit {
+            max_relative_deviation: Decimal::from_str("0.1").unwrap(),
+            scaling_period: None,
+            action: DeviationAction::Reject,
+        };
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: Some(limit.clone()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::RateDeviationLimits {
+            start_after: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let limits: RateDeviationLimitsResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            limits.limits,
+            vec![RateDeviationLimitEntry {
+                denom: STTOKEN_DENOM.to_string(),
+                limit: limit.clone(),
+            }]
+        );
+
+        // A small move should be accepted, but a large move should be rejected
+        post(&mut deps, "1.05", 2).unwrap();
+        let resp = post(&mut deps, "1.5", 3);
+        assert_eq!(
+            resp,
+            Err(ContractError::RateDeviationExceeded {
+                denom: STTOKEN_DENOM.to_string(),
+                previous: Decimal::from_str("1.05").unwrap(),
+                new: Decimal::from_str("1.5").unwrap(),
+                max_deviation: Decimal::from_str("0.1").unwrap(),
+            })
+        );
+        assert_eq!(latest_rate(&deps).update_time, 2);
+
+        // With a scaling period, the allowed deviation grows with the time since the last update
+        // 98 seconds have elapsed, which is 9.8 periods, so the max deviation is 98%
+        limit.scaling_period = Some(10);
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: Some(limit.clone()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        post(&mut deps, "1.5", 100).unwrap();
+        assert_eq!(
+            latest_rate(&deps).redemption_rate,
+            Decimal::from_str("1.5").unwrap()
+        );
+
+        // Switch to quarantine, the metric should be recorded but the rate should not be updated
+        limit.action = DeviationAction::Quarantine;
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: Some(limit),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let resp = post(&mut deps, "5.0", 101).unwrap();
+        assert_eq!(
+            resp.attributes.last(),
+            Some(&attr("rate_outcome", "quarantined"))
+        );
+        assert_eq!(latest_rate(&deps).update_time, 100);
+
+        let msg = QueryMsg::Metric {
+            key: "key1".to_string(),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let metric: Metric = from_binary(&resp).unwrap();
+        assert_eq!(metric.value, "5.0");
+
+        // Once the limit is removed, any rate is accepted
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: None,
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+        post(&mut deps, "5.0", 102).unwrap();
+        assert_eq!(latest_rate(&deps).update_time, 102);
+    }
+
+    #[test]
+    fn test_monotonic_redemption_rate() {
+        // Instantiate contract and post an initial redemption rate
+        let (mut deps, env, info) = default_instantiate();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    value: &str,
+                    time: u64,
+                    slash: bool| {
+            let mut metric = get_test_redemption_rate_metric("key1", value, time);
+            let attributes = RedemptionRateAttributes {
+                sttoken_denom: STTOKEN_DENOM.to_string(),
+                slash,
+            };
+            metric.attributes = Some(to_binary(&attributes).unwrap());
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(&metric),
+            )
+        };
+        let get_slash_events_page = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                                     start_after: Option<u64>,
+                                     order: Option<HistoryOrder>,
+                                     limit: Option<u64>| {
+            let msg = QueryMsg::SlashEvents {
+                denom: STTOKEN_DENOM.to_string(),
+                start_time: None,
+                end_time: None,
+                start_after,
+                order,
+                limit,
+            };
+            let resp = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<SlashEvents>(&resp)
+                .unwrap()
+                .slash_events
+                .iter()
+                .map(|slash_event| slash_event.update_time)
+                .collect::<Vec<_>>()
+        };
+        let get_slash_events = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            let msg = QueryMsg::SlashEvents {
+                denom: STTOKEN_DENOM.to_string(),
+                start_time: None,
+                end_time: None,
+                start_after: None,
+                order: None,
+                limit: None,
+            };
+            let resp = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<SlashEvents>(&resp).unwrap().slash_events
+        };
+        post(&mut deps, "1.2", 1, false).unwrap();
+
+        // Without the policy, the redemption rate can decrease freely
+        post(&mut deps, "1.1", 2, false).unwrap();
+        assert_eq!(get_slash_events(&deps), vec![]);
+
+        // Enable the policy
+        let msg = ExecuteMsg::SetMonotonicRedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            enabled: true,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::MonotonicDenoms {
+            start_after: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let denoms: MonotonicDenomsResponse = from_binary(&resp).unwrap();
+        assert_eq!(denoms.denoms, vec![STTOKEN_DENOM.to_string()]);
+
+        // Increases are accepted, but an unflagged decrease is rejected
+        post(&mut deps, "1.3", 3, false).unwrap();
+        let resp = post(&mut deps, "1.25", 4, false);
+        assert_eq!(
+            resp,
+            Err(ContractError::RedemptionRateDecrease {
+                denom: STTOKEN_DENOM.to_string(),
+                previous: Decimal::from_str("1.3").unwrap(),
+                new: Decimal::from_str("1.25").unwrap(),
+            })
+        );
+
+        // A decrease flagged as a slash is accepted and recorded
+        let resp = post(&mut deps, "1.25", 4, true).unwrap();
+        assert_eq!(
+            resp.attributes.last(),
+            Some(&attr("slash_recorded", "true"))
+        );
+        let expected_slash = SlashEvent {
+            denom: STTOKEN_DENOM.to_string(),
+            previous_redemption_rate: Decimal::from_str("1.3").unwrap(),
+            redemption_rate: Decimal::from_str("1.25").unwrap(),
+            update_time: 4,
+            block_height: 4,
+        };
+        assert_eq!(get_slash_events(&deps), vec![expected_slash.clone()]);
+
+        // A slash flag on an increase does not record an event
+        let resp = post(&mut deps, "1.4", 5, true).unwrap();
+        assert_ne!(
+            resp.attributes.last(),
+            Some(&attr("slash_recorded", "true"))
+        );
+        assert_eq!(get_slash_events(&deps), vec![expected_slash]);
+
+        // A backfilled rate is checked against the rates around it, rather than the latest rate
+        post(&mut deps, "1.5", 10, false).unwrap();
+        post(&mut deps, "1.6", 20, false).unwrap();
+        let resp = post(&mut deps, "1.45", 15, false);
+        assert_eq!(
+            resp,
+            Err(ContractError::RedemptionRateDecrease {
+                denom: STTOKEN_DENOM.to_string(),
+                previous: Decimal::from_str("1.5").unwrap(),
+                new: Decimal::from_str("1.45").unwrap(),
+            })
+        );
+        let resp = post(&mut deps, "1.7", 15, false);
+        assert_eq!(
+            resp,
+            Err(ContractError::RedemptionRateExceedsNext {
+                denom: STTOKEN_DENOM.to_string(),
+                new: Decimal::from_str("1.7").unwrap(),
+                update_time: 15,
+                next: Decimal::from_str("1.6").unwrap(),
+                next_update_time: 20,
+            })
+        );
+        post(&mut deps, "1.55", 15, false).unwrap();
+
+        // A backfilled rate can exceed the following rate if that rate was recorded as a slash
+        post(&mut deps, "1.5", 30, true).unwrap();
+        post(&mut deps, "1.65", 25, false).unwrap();
+        assert_eq!(get_slash_events(&deps).len(), 2);
+
+        // Disable the policy, unflagged decreases are accepted again
+        let msg = ExecuteMsg::SetMonotonicRedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            enabled: false,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        post(&mut deps, "1.0", 40, false).unwrap();
+        assert_eq!(get_slash_events(&deps).len(), 2);
+
+        // The slash events are paginated by time, newest first by default
+        assert_eq!(get_slash_events_page(&deps, None, None, Some(1)), vec![30]);
+        assert_eq!(get_slash_events_page(&deps, Some(30), None, None), vec![4]);
+        assert_eq!(
+            get_slash_events_page(&deps, None, Some(HistoryOrder::Ascending), None),
+            vec![4, 30]
+        );
+
+        // Removing a slashed metric leaves its slash event in place
+        let msg = SudoMsg::RemoveMetric {
+            key: "key1".to_string(),
+            update_time: Some(30),
+        };
+        sudo(deps.as_mut(), env, msg).unwrap();
+        assert_eq!(get_slash_events_page(&deps, None, None, None), vec![30, 4]);
+    }
+
+    #[test]
+    fn test_metric_bounds() {
+        let (mut deps, env, info) = default_instantiate();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    metric: &Metric| {
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(metric),
+            )
+        };
+
+        // Bounds with a min greater than the max are rejected
+        let invalid_bounds = MetricBounds {
+            min: Some(Decimal::from_str("3.0").unwrap()),
+            max: Some(Decimal::from_str("1.0").unwrap()),
+        };
+        let msg = ExecuteMsg::SetMetricBounds {
+            target: BoundsTarget::Key("key1".to_string()),
+            bounds: Some(invalid_bounds.clone()),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(
+            resp,
+            Err(ContractError::InvalidMetricBounds {
+                bounds: invalid_bounds
+            })
+        );
+
+        // Bound key1 to [1.0, 3.0] and the denom to [-inf, 2.0]
+        let key_bounds = MetricBounds {
+            min: Some(Decimal::from_str("1.0").unwrap()),
+            max: Some(Decimal::from_str("3.0").unwrap()),
+        };
+        let msg = ExecuteMsg::SetMetricBounds {
+            target: BoundsTarget::Key("key1".to_string()),
+            bounds: Some(key_bounds.clone()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let denom_bounds = MetricBounds {
+            min: None,
+            max: Some(Decimal::from_str("2.0").unwrap()),
+        };
+        let msg = ExecuteMsg::SetMetricBounds {
+            target: BoundsTarget::Denom(STTOKEN_DENOM.to_string()),
+            bounds: Some(denom_bounds.clone()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::MetricBounds {}).unwrap();
+        let bounds: MetricBoundsResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            bounds.bounds,
+            vec![
+                MetricBoundsEntry {
+                    target: BoundsTarget::Key("key1".to_string()),
+                    bounds: key_bounds.clone(),
+                },
+                MetricBoundsEntry {
+                    target: BoundsTarget::Denom(STTOKEN_DENOM.to_string()),
+                    bounds: denom_bounds.clone(),
+                },
+            ]
+        );
+
+        // A value below the key's min is rejected
+        let metric = get_test_redemption_rate_metric("key1", "0.5", 1);
+        assert_eq!(
+            post(&mut deps, &metric),
+            Err(ContractError::MetricOutOfBounds {
+                target: BoundsTarget::Key("key1".to_string()),
+                value: Decimal::from_str("0.5").unwrap(),
+                bounds: key_bounds,
+            })
+        );
+
+        // A value within the key's bounds, but above the denom's max, is rejected
+        let metric = get_test_redemption_rate_metric("key1", "2.5", 1);
+        assert_eq!(
+            post(&mut deps, &metric),
+            Err(ContractError::MetricOutOfBounds {
+                target: BoundsTarget::Denom(STTOKEN_DENOM.to_string()),
+                value: Decimal::from_str("2.5").unwrap(),
+                bounds: denom_bounds.clone(),
+            })
+        );
+
+        // A value within both bounds is accepted
+        let metric = get_test_redemption_rate_metric("key1", "1.5", 1);
+        post(&mut deps, &metric).unwrap();
+
+        // Non-numeric values are not subject to the bounds
+        let metric = Metric {
+            key: "key1".to_string(),
+            value: "not-a-number".to_string(),
+            metric_type: MetricType::Other("other".to_string()),
+            update_time: 2,
+            block_height: 2,
+            attributes: None,
+            received_time: None,
+            received_height: None,
+        };
+        post(&mut deps, &metric).unwrap();
+
+        // Once the key bounds are removed, only the denom bounds apply
+        let msg = ExecuteMsg::SetMetricBounds {
+            target: BoundsTarget::Key("key1".to_string()),
+            bounds: None,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let metric = get_test_redemption_rate_metric("key1", "0.5", 3);
+        post(&mut deps, &metric).unwrap();
+
+        let resp = query(deps.as_ref(), env, QueryMsg::MetricBounds {}).unwrap();
+        let bounds: MetricBoundsResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            bounds.bounds,
+            vec![MetricBoundsEntry {
+                target: BoundsTarget::Denom(STTOKEN_DENOM.to_string()),
+                bounds: denom_bounds,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_circuit_breaker() {
+        // Instantiate contract and post an initial redemption rate
+        let (mut deps, env, info) = default_instantiate();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    value: &str,
+                    time: u64| {
+            let metric = get_test_redemption_rate_metric("key1", value, time);
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(&metric),
+            )
+        };
+        let redemption_rate_query = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+        let pending_updates_query = QueryMsg::PendingUpdates {
+            start_after: None,
+            limit: None,
+        };
+        let latest_metric_time = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            let msg = QueryMsg::Metric {
+                key: "key1".to_string(),
+            };
+            let resp = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<Metric>(&resp).unwrap().update_time
+        };
+        post(&mut deps, "1.0", 1).unwrap();
+
+        // Configure a 10% limit with the circuit breaker
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: Some(RateDeviationLimit {
+                max_relative_deviation: Decimal::from_str("0.1").unwrap(),
+                scaling_period: None,
+                action: DeviationAction::CircuitBreaker,
+            }),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::SetEmaHalfLife {
+            key: "key1".to_string(),
+            half_life: Some(100),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // An anomalous update should trip the breaker, report the metric as held, and emit an event
+        let resp = post(&mut deps, "2.0", 2).unwrap();
+        assert_eq!(
+            from_binary::<PostMetricResponse>(&resp.data.clone().unwrap()).unwrap(),
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Held,
+                rate_outcome: Some(RateOutcome::CircuitBreakerTripped),
+            }
+        );
+        assert!(resp
+            .attributes
+            .contains(&attr("metric_outcome", MetricOutcome::Held.to_string())));
+        assert_eq!(
+            resp.events,
+            vec![Event::new("circuit_breaker_tripped").add_attributes(vec![
+                attr("denom", STTOKEN_DENOM),
+                attr("previous_rate", "1"),
+                attr("new_rate", "2"),
+                attr("max_deviation", "0.1"),
+            ])]
+        );
+
+        // The update should be pending and the denom should be held
+        let resp = query(deps.as_ref(), env.clone(), pending_updates_query.clone()).unwrap();
+        let pending_updates: PendingUpdatesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            pending_updates.updates,
+            vec![PendingRateUpdate {
+                denom: STTOKEN_DENOM.to_string(),
+                metric: get_test_redemption_rate_metric("key1", "2.0", 2),
+                update: RateUpdate::RedemptionRate(get_test_redemption_rate("2.0", 2)),
+                previous_rate: Decimal::from_str("1.0").unwrap(),
+                max_deviation: Decimal::from_str("0.1").unwrap(),
+                slash_event: None,
+            }]
+        );
+
+        let held_error = || ContractError::DenomHeld {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        let resp = query(deps.as_ref(), env.clone(), redemption_rate_query.clone());
+        assert_eq!(resp, Err(held_error()));
+        assert_eq!(post(&mut deps, "1.0", 3), Err(held_error()));
+
+        // The breaker should not be reported as a manual freeze, and unfreezing the denom
+        // should not release the held update or allow it to be overwritten
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::FreezeStatus {}).unwrap();
+        let freeze_status: FreezeStatusResponse = from_binary(&resp).unwrap();
+        assert!(freeze_status.frozen_denoms.is_empty());
+
+        let msg = ExecuteMsg::UnfreezeDenom {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(post(&mut deps, "3.0", 3), Err(held_error()));
+
+        let resp = query(deps.as_ref(), env.clone(), pending_updates_query.clone()).unwrap();
+        let pending_updates: PendingUpdatesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            pending_updates.updates[0].update.rate(),
+            Decimal::from_str("2.0").unwrap()
+        );
+
+        // The metric should be held with the update, rather than stored
+        assert_eq!(latest_metric_time(&deps), 1);
+
+        // Only the admin or guardian can review the update
+        let msg = ExecuteMsg::ApprovePendingUpdate {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        let resp = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("not_guardian", &[]),
+            msg.clone(),
+        );
+        assert_eq!(resp, Err(ContractError::Unauthorized {}));
+
+        // The update can't be approved while the contract is paused
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        assert_eq!(resp, Err(ContractError::Paused {}));
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+
+        // The update can't be approved while the metric's key is frozen
+        let freeze_key_msg = ExecuteMsg::FreezeKey {
+            key: "key1".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), freeze_key_msg).unwrap();
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg.clone());
+        assert_eq!(
+            resp,
+            Err(ContractError::KeyFrozen {
+                key: "key1".to_string()
+            })
+        );
+        let unfreeze_key_msg = ExecuteMsg::UnfreezeKey {
+            key: "key1".to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), unfreeze_key_msg).unwrap();
+
+        // Approving the update should store the rate, but leave a manual freeze in place
+        let freeze_msg = ExecuteMsg::FreezeDenom {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), freeze_msg).unwrap();
+        execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), redemption_rate_query.clone());
+        assert_eq!(
+            resp,
+            Err(ContractError::DenomFrozen {
+                denom: STTOKEN_DENOM.to_string(),
+            })
+        );
+        let unfreeze_msg = ExecuteMsg::UnfreezeDenom {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), unfreeze_msg).unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), redemption_rate_query.clone()).unwrap();
+        let redemption_rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            redemption_rate,
+            RedemptionRateResponse {
+                redemption_rate: Decimal::from_str("2.0").unwrap(),
+                update_time: 2,
+                received_time: Some(env.block.time.seconds()),
+                received_height: Some(env.block.height),
+            }
+        );
+
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg);
+        assert_eq!(
+            resp,
+            Err(ContractError::PendingUpdateNotFound {
+                denom: STTOKEN_DENOM.to_string()
+            })
+        );
+
+        // The held metric should be stored, and included in the key's moving average
+        assert_eq!(latest_metric_time(&deps), 2);
+        let msg = QueryMsg::Ema {
+            key: "key1".to_string(),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let ema: EmaResponse = from_binary(&resp).unwrap();
+        assert_eq!(ema.update_time, 2);
+
+        // The approved rate should be included in the denom's moving average
+        let params = RateQueryParams {
+            ema: true,
+            ..Default::default()
+        };
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: Some(to_binary(&params).unwrap()),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let redemption_rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(redemption_rate.update_time, 2);
+
+        // Trip the breaker again, and this time reject the update
+        post(&mut deps, "4.0", 4).unwrap();
+        let msg = ExecuteMsg::RejectPendingUpdate {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        // The rate and metric should be unchanged, and there should be no pending updates
+        let resp = query(deps.as_ref(), env.clone(), redemption_rate_query).unwrap();
+        let redemption_rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(redemption_rate.update_time, 2);
+        assert_eq!(latest_metric_time(&deps), 2);
+
+        let resp = query(deps.as_ref(), env, pending_updates_query).unwrap();
+        let pending_updates: PendingUpdatesResponse = from_binary(&resp).unwrap();
+        assert_eq!(pending_updates.updates, vec![]);
+    }
+
+    #[test]
+    fn test_circuit_breaker_slash() {
+        // Instantiate contract with a monotonic denom and a 10% circuit breaker
+        let (mut deps, env, info) = default_instantiate();
+        let msg = ExecuteMsg::SetMonotonicRedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            enabled: true,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: Some(RateDeviationLimit {
+                max_relative_deviation: Decimal::from_str("0.1").unwrap(),
+                scaling_period: None,
+                action: DeviationAction::CircuitBreaker,
+            }),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // Post a rate, followed by a large slash that trips the breaker
+        let metric = get_test_redemption_rate_metric("key1", "1.5", 1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+
+        let mut metric = get_test_redemption_rate_metric("key1", "1.0", 2);
+        let attributes = RedemptionRateAttributes {
+            sttoken_denom: STTOKEN_DENOM.to_string(),
+            slash: true,
+        };
+        metric.attributes = Some(to_binary(&attributes).unwrap());
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+
+        let slash_events_query = QueryMsg::SlashEvents {
+            denom: STTOKEN_DENOM.to_string(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), slash_events_query.clone()).unwrap();
+        let slash_events: SlashEvents = from_binary(&resp).unwrap();
+        assert_eq!(slash_events.slash_events, vec![]);
+
+        // Once the update is approved, the slash should be recorded
+        let msg = ExecuteMsg::ApprovePendingUpdate {
+            denom: STTOKEN_DENOM.to_string(),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        assert_eq!(
+            resp.attributes.last(),
+            Some(&attr("slash_recorded", "true"))
+        );
+
+        let resp = query(deps.as_ref(), env, slash_events_query).unwrap();
+        let slash_events: SlashEvents = from_binary(&resp).unwrap();
+        assert_eq!(
+            slash_events.slash_events,
+            vec![SlashEvent {
+                denom: STTOKEN_DENOM.to_string(),
+                previous_redemption_rate: Decimal::from_str("1.5").unwrap(),
+                redemption_rate: Decimal::from_str("1.0").unwrap(),
+                update_time: 2,
+                block_height: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rate_max_age() {
+        // Instantiate contract and post redemption and purchase rates 100 seconds ago
+        let (mut deps, env, info) = default_instantiate();
+        let current_time = env.block.time.seconds();
+        let update_time = current_time - 100;
+
+        let metric = get_test_redemption_rate_metric("key1", "1.1", update_time);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+        let metric = get_test_purchase_rate_metric("key2", "1.2", update_time);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+
+        let redemption_rate_query = |max_age: Option<u64>| QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: max_age.map(|max_age| {
+                to_binary(&RateQueryParams {
+                    max_age: Some(max_age),
+                    ..Default::default()
+                })
+                .unwrap()
+            }),
+        };
+        let purchase_rate_query = |max_age: Option<u64>| QueryMsg::PurchaseRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: max_age.map(|max_age| {
+                to_binary(&RateQueryParams {
+                    max_age: Some(max_age),
+                    ..Default::default()
+                })
+                .unwrap()
+            }),
+        };
+        let stale_error = |max_age: u64| {
+            Err(ContractError::StaleRate {
+                denom: STTOKEN_DENOM.to_string(),
+                update_time,
+                current_time,
+                max_age,
+            })
+        };
+
+        // Without a max age, the rates are returned regardless of age
+        query(deps.as_ref(), env.clone(), redemption_rate_query(None)).unwrap();
+        query(deps.as_ref(), env.clone(), purchase_rate_query(None)).unwrap();
+
+        // The max age passed in the params is enforced
+        query(deps.as_ref(), env.clone(), redemption_rate_query(Some(100))).unwrap();
+        let resp = query(deps.as_ref(), env.clone(), redemption_rate_query(Some(99)));
+        assert_eq!(resp, stale_error(99));
+
+        // Params that can't be decoded are rejected
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: Some(to_binary("invalid").unwrap()),
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap_err();
+
+        // Set a default max age for the denom
+        let msg = ExecuteMsg::SetRateMaxAge {
+            denom: STTOKEN_DENOM.to_string(),
+            max_age: Some(60),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::RateMaxAges {
+            start_after: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let max_ages: RateMaxAgesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            max_ages.max_ages,
+            vec![RateMaxAgeEntry {
+                denom: STTOKEN_DENOM.to_string(),
+                max_age: 60,
+            }]
+        );
+
+        // Queries without params now use the default, while params take precedence
+        let resp = query(deps.as_ref(), env.clone(), redemption_rate_query(None));
+        assert_eq!(resp, stale_error(60));
+        let resp = query(deps.as_ref(), env.clone(), purchase_rate_query(None));
+        assert_eq!(resp, stale_error(60));
+        query(deps.as_ref(), env.clone(), redemption_rate_query(Some(200))).unwrap();
+
+        // Once the clock is rolled back, the rate is fresh again
+        let mut earlier_env = env.clone();
+        earlier_env.block.time = earlier_env.block.time.minus_seconds(50);
+        query(deps.as_ref(), earlier_env, redemption_rate_query(None)).unwrap();
+
+        // Removing the default max age lifts the restriction
+        let msg = ExecuteMsg::SetRateMaxAge {
+            denom: STTOKEN_DENOM.to_string(),
+            max_age: None,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+        query(deps.as_ref(), env, redemption_rate_query(None)).unwrap();
+    }
+
+    #[test]
+    fn test_health() {
+        let (mut deps, env, info) = default_instantiate();
+        let current_time = env.block.time.seconds();
+
+        // Post metrics that were updated 30, 100 and 200 seconds ago
+        for (key, seconds_ago) in [("key1", 30), ("key2", 100), ("key3", 200), ("key4", 200)] {
+            let metric = Metric {
+                key: key.to_string(),
+                value: "1".to_string(),
+                metric_type: MetricType::Other("other".to_string()),
+                update_time: current_time - seconds_ago,
+                block_height: 1,
+                attributes: None,
+                received_time: None,
+                received_height: None,
+            };
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+        }
+
+        // Configure a 60 second heartbeat for each key except key4
+        // key5 has a heartbeat, but has never been posted
+        for key in ["key1", "key2", "key3", "key5"] {
+            let msg = ExecuteMsg::SetHeartbeat {
+                key: key.to_string(),
+                interval: Some(60),
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+
+        let key_health = |key: &str, seconds_ago: Option<u64>, interval, status| KeyHealth {
+            key: key.to_string(),
+            last_update_time: seconds_ago.map(|seconds_ago| current_time - seconds_ago),
+            seconds_since_update: seconds_ago,
+            heartbeat_interval: interval,
+            status,
+        };
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Health {}).unwrap();
+        let health: HealthResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            health.keys,
+            vec![
+                key_health("key1", Some(30), Some(60), HealthStatus::Ok),
+                key_health("key2", Some(100), Some(60), HealthStatus::Late),
+                key_health("key3", Some(200), Some(60), HealthStatus::Stale),
+                key_health("key4", Some(200), None, HealthStatus::Unmonitored),
+                key_health("key5", None, Some(60), HealthStatus::Stale),
+            ]
+        );
+
+        // Once the heartbeat is removed, key5 is no longer reported
+        let msg = ExecuteMsg::SetHeartbeat {
+            key: "key5".to_string(),
+            interval: None,
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let resp = query(deps.as_ref(), env, QueryMsg::Health {}).unwrap();
+        let health: HealthResponse = from_binary(&resp).unwrap();
+        assert_eq!(health.keys.len(), 4);
+    }
+
+    #[test]
+    fn test_metric_time_limits() {
+        let (mut deps, env, info) = default_instantiate();
+        let current_time = env.block.time.seconds();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    update_time: u64| {
+            let metric = get_test_redemption_rate_metric("key1", "1.0", update_time);
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(&metric),
+            )
+        };
+
+        // Without limits, metrics from the far future and past are accepted
+        post(&mut deps, 1).unwrap();
+        post(&mut deps, current_time + 1_000_000).unwrap();
+
+        // Allow up to 60 seconds of clock skew and metrics up to an hour old
+        let msg = ExecuteMsg::SetMetricTimeLimits {
+            max_clock_skew: Some(60),
+            max_metric_age: Some(3600),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let resp = query(deps.as_ref(), env.clone(), QueryMsg::Config {}).unwrap();
+        let config: Config = from_binary(&resp).unwrap();
+        assert_eq!(config.max_clock_skew, Some(60));
+        assert_eq!(config.max_metric_age, Some(3600));
+
+        // Metrics within the limits are accepted
+        post(&mut deps, current_time + 60).unwrap();
+        post(&mut deps, current_time - 3600).unwrap();
+
+        // Metrics outside of the limits are rejected
+        assert_eq!(
+            post(&mut deps, current_time + 61),
+            Err(ContractError::MetricTimestampInFuture {
+                key: "key1".to_string(),
+                update_time: current_time + 61,
+                max_time: current_time + 60,
+            })
+        );
+        assert_eq!(
+            post(&mut deps, current_time - 3601),
+            Err(ContractError::MetricTooOld {
+                key: "key1".to_string(),
+                update_time: current_time - 3601,
+                min_time: current_time - 3600,
+            })
+        );
+
+        // Removing the limits allows any update time again
+        let msg = ExecuteMsg::SetMetricTimeLimits {
+            max_clock_skew: None,
+            max_metric_age: None,
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+        post(&mut deps, current_time + 61).unwrap();
+    }
+
+    #[test]
+    fn test_received_time_and_height() {
+        let (mut deps, mut env, info) = default_instantiate();
+
+        // Post a redemption rate at a later block on the oracle chain
+        env.block.time = env.block.time.plus_seconds(30);
+        env.block.height += 5;
+        let metric = get_test_redemption_rate_metric("key1", "1.1", 1);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            info,
+            get_post_metric_msg(&metric),
+        )
+        .unwrap();
+
+        // The receipt time and height should be recorded on the metric and rate
+        let msg = QueryMsg::Metric {
+            key: "key1".to_string(),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let metric: Metric = from_binary(&resp).unwrap();
+        assert_eq!(metric.received_time, Some(env.block.time.seconds()));
+        assert_eq!(metric.received_height, Some(env.block.height));
+
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let redemption_rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            redemption_rate.received_time,
+            Some(env.block.time.seconds())
+        );
+        assert_eq!(redemption_rate.received_height, Some(env.block.height));
+    }
+
+    #[test]
+    fn test_insert_policy() {
+        let (mut deps, env, info) = default_instantiate();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    value: &str,
+                    update_time: u64,
+                    block_height: u64| {
+            let metric = Metric {
+                key: "key1".to_string(),
+                value: value.to_string(),
+                metric_type: MetricType::Other("other".to_string()),
+                update_time,
+                block_height,
+                attributes: None,
+                received_time: None,
+                received_height: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(&metric),
+            )
+        };
+        let latest_value = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            let msg = QueryMsg::Metric {
+                key: "key1".to_string(),
+            };
+            let resp = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<Metric>(&resp).unwrap().value
+        };
+
+        // With the default policy, metrics can be backfilled and replaced
+        post(&mut deps, "1", 10, 100).unwrap();
+        post(&mut deps, "2", 5, 50).unwrap();
+        post(&mut deps, "3", 10, 100).unwrap();
+        assert_eq!(latest_value(&deps), "3");
+
+        // Regardless of policy, a metric with the same time but a lower height is rejected,
+        // while a greater height replaces the existing metric
+        assert_eq!(
+            post(&mut deps, "4", 10, 99),
+            Err(ContractError::MetricSuperseded {
+                key: "key1".to_string(),
+                update_time: 10,
+                block_height: 99,
+                existing_block_height: 100,
+            })
+        );
+        post(&mut deps, "5", 10, 101).unwrap();
+        assert_eq!(latest_value(&deps), "5");
+
+        // Require strictly increasing metrics and reject conflicting duplicates
+        let policy = InsertPolicy {
+            ordering: InsertOrdering::StrictlyIncreasing,
+            duplicates: DuplicatePolicy::RejectConflicting,
+        };
+        let msg = ExecuteMsg::SetInsertPolicy {
+            key: "key1".to_string(),
+            policy: Some(policy.clone()),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::InsertPolicies {
+            start_after: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let policies: InsertPoliciesResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            policies.policies,
+            vec![InsertPolicyEntry {
+                key: "key1".to_string(),
+                policy,
+            }]
+        );
+
+        // Backfilled metrics are now rejected
+        let not_newer_error = |update_time, block_height| {
+            Err(ContractError::MetricNotNewer {
+                key: "key1".to_string(),
+                update_time,
+                block_height,
+                latest_update_time: 10,
+                latest_block_height: 101,
+            })
+        };
+        assert_eq!(post(&mut deps, "6", 6, 60), not_newer_error(6, 60));
+        assert_eq!(post(&mut deps, "6", 10, 101), not_newer_error(10, 101));
+
+        // A newer metric, by either time or height, is accepted
+        post(&mut deps, "7", 10, 102).unwrap();
+        post(&mut deps, "8", 11, 110).unwrap();
+        assert_eq!(latest_value(&deps), "8");
+
+        // Allow backfill, but keep rejecting conflicting duplicates
+        let msg = ExecuteMsg::SetInsertPolicy {
+            key: "key1".to_string(),
+            policy: Some(InsertPolicy {
+                ordering: InsertOrdering::AllowBackfill,
+                duplicates: DuplicatePolicy::RejectConflicting,
+            }),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        // An identical duplicate is accepted, while a conflicting duplicate is rejected
+        post(&mut deps, "2", 5, 50).unwrap();
+        assert_eq!(
+            post(&mut deps, "9", 5, 50),
+            Err(ContractError::ConflictingMetric {
+                key: "key1".to_string(),
+                update_time: 5,
+            })
+        );
+
+        // Removing the policy restores the default behavior
+        let msg = ExecuteMsg::SetInsertPolicy {
+            key: "key1".to_string(),
+            policy: None,
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+        post(&mut deps, "9", 5, 50).unwrap();
+    }
+
+    #[test]
+    fn test_post_metric_outcome() {
+        let (mut deps, env, info) = default_instantiate();
+        let mut post = |metric: &Metric| -> PostMetricResponse {
+            let msg = get_post_metric_msg(metric);
+            let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            from_binary(&resp.data.unwrap()).unwrap()
+        };
+
+        // The first post for a given time is inserted, and a second post replaces it
+        let metric = get_test_redemption_rate_metric("key1", "1.0", 1000);
+        assert_eq!(
+            post(&metric),
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Inserted,
+                rate_outcome: Some(RateOutcome::Inserted),
+            }
+        );
+        assert_eq!(
+            post(&metric),
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Replaced,
+                rate_outcome: Some(RateOutcome::Replaced),
+            }
+        );
+
+        // Fill the history to capacity
+        for time in 1001..1100 {
+            post(&get_test_redemption_rate_metric("key1", "1.0", time));
+        }
+
+        // A metric older than every entry is dropped from both stores
+        let metric = get_test_redemption_rate_metric("key1", "1.0", 1);
+        assert_eq!(
+            post(&metric),
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Dropped,
+                rate_outcome: Some(RateOutcome::Dropped),
+            }
+        );
+
+        // Metrics that aren't rates only report the metric outcome
+        let metric = Metric {
+            key: "key2".to_string(),
+            value: "value".to_string(),
+            metric_type: MetricType::Other("other".to_string()),
+            update_time: 1,
+            block_height: 1,
+            attributes: None,
+            received_time: None,
+            received_height: None,
+        };
+        assert_eq!(
+            post(&metric),
+            PostMetricResponse {
+                metric_outcome: MetricOutcome::Inserted,
+                rate_outcome: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_post_metrics() {
+        let (mut deps, env, info) = default_instantiate();
+
+        // Post a redemption rate and a purchase rate in the same batch
+        let redemption_rate_metric = get_test_redemption_rate_metric("key1", "1.1", 1);
+        let purchase_rate_metric = get_test_purchase_rate_metric("key2", "1.2", 1);
+        let msg = ExecuteMsg::PostMetrics {
+            metrics: vec![
+                get_metric_update(&redemption_rate_metric),
+                get_metric_update(&purchase_rate_metric),
+            ],
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(
+            resp.attributes,
+            vec![attr("action", "post_metrics"), attr("batch_size", "2")]
+        );
+
+        // Each metric is emitted under its own event
+        let metric_keys: Vec<String> = resp
+            .events
+            .iter()
+            .filter(|event| event.ty == "post_metric")
+            .map(|event| event.attributes[0].value.clone())
+            .collect();
+        assert_eq!(metric_keys, vec!["key1", "key2"]);
+
+        let data: PostMetricsResponse = from_binary(&resp.data.unwrap()).unwrap();
+        let inserted = PostMetricResponse {
+            metric_outcome: MetricOutcome::Inserted,
+            rate_outcome: Some(RateOutcome::Inserted),
+        };
+        assert_eq!(data.outcomes, vec![inserted.clone(), inserted]);
+
+        // Both metrics and the derived rates should be stored
+        for metric in [&redemption_rate_metric, &purchase_rate_metric] {
+            let msg = QueryMsg::Metric {
+                key: metric.key.clone(),
+            };
+            let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+            assert_eq!(from_binary::<Metric>(&resp).unwrap(), metric.clone());
+        }
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap();
+        let msg = QueryMsg::PurchaseRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap();
+
+        // A batch is rejected if any one of its metrics is invalid
+        let mut invalid_metric = get_test_redemption_rate_metric("key1", "1.1", 2);
+        invalid_metric.attributes = None;
+        let msg = ExecuteMsg::PostMetrics {
+            metrics: vec![
+                get_metric_update(&get_test_redemption_rate_metric("key1", "1.1", 3)),
+                get_metric_update(&invalid_metric),
+            ],
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), info.clone(), msg),
+            Err(ContractError::MissingMetricMetadataAttributes {
+                metric_type: MetricType::RedemptionRate,
+            })
+        );
+
+        // Batches are also subject to the poster role
+        let msg = ExecuteMsg::PostMetrics {
+            metrics: vec![get_metric_update(&redemption_rate_metric)],
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("non-poster", &[]),
+                msg
+            ),
+            Err(ContractError::Unauthorized {})
+        );
+
+        // Empty batches are rejected
+        let msg = ExecuteMsg::PostMetrics { metrics: vec![] };
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), info.clone(), msg),
+            Err(ContractError::EmptyBatch {})
+        );
+
+        // A max batch size of zero is rejected, since it would block every batch
+        let msg = ExecuteMsg::SetMaxBatchSize {
+            max_batch_size: Some(0),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), info.clone(), msg),
+            Err(ContractError::InvalidMaxBatchSize {})
+        );
+
+        // Batches larger than the max batch size are rejected
+        let msg = ExecuteMsg::SetMaxBatchSize {
+            max_batch_size: Some(1),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::PostMetrics {
+            metrics: vec![
+                get_metric_update(&get_test_redemption_rate_metric("key1", "1.1", 4)),
+                get_metric_update(&get_test_redemption_rate_metric("key1", "1.1", 5)),
+            ],
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), info.clone(), msg),
+            Err(ContractError::BatchTooLarge {
+                size: 2,
+                max_batch_size: 1,
+            })
+        );
+
+        // Removing the max restores the default
+        let msg = ExecuteMsg::SetMaxBatchSize {
+            max_batch_size: None,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = ExecuteMsg::PostMetrics {
+            metrics: vec![
+                get_metric_update(&get_test_redemption_rate_metric("key1", "1.1", 4)),
+                get_metric_update(&get_test_redemption_rate_metric("key1", "1.1", 5)),
+            ],
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+    }
+
+    #[test]
+    fn test_post_typed_rates() {
+        let (mut deps, env, info) = default_instantiate();
+
+        // Post a redemption rate with the default key
+        let msg = ExecuteMsg::PostRedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            redemption_rate: Decimal::from_str("1.1").unwrap(),
+            update_time: 1,
+            block_height: 1,
+            key: None,
+            slash: false,
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(resp.attributes[0], attr("action", "post_redemption_rate"));
+
+        // The metric should be stored exactly as if it was posted with PostMetric
+        let default_key = format!("{STTOKEN_DENOM}_redemption_rate");
+        let expected_metric = get_test_redemption_rate_metric(&default_key, "1.1", 1);
+        let msg = QueryMsg::Metric { key: default_key };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(from_binary::<Metric>(&resp).unwrap(), expected_metric);
+
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let redemption_rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            redemption_rate.redemption_rate,
+            Decimal::from_str("1.1").unwrap()
+        );
+
+        // A later rate posted with PostMetric under the same key extends the same history
+        let metric = get_test_redemption_rate_metric(&expected_metric.key, "1.2", 2);
+        let msg = get_post_metric_msg(&metric);
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        let msg = QueryMsg::HistoricalMetrics {
+            key: expected_metric.key.clone(),
+            start_time: None,
+            end_time: None,
+            start_after: None,
+            order: None,
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let metrics: Metrics = from_binary(&resp).unwrap();
+        assert_eq!(metrics.metrics, vec![metric, expected_metric]);
+
+        // Post a purchase rate with a custom key
+        let msg = ExecuteMsg::PostPurchaseRate {
+            denom: STTOKEN_DENOM.to_string(),
+            purchase_rate: Decimal::from_str("1.3").unwrap(),
+            update_time: 1,
+            block_height: 1,
+            key: Some("key1".to_string()),
+        };
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        assert_eq!(resp.attributes[0], attr("action", "post_purchase_rate"));
+        let data: PostMetricResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data.rate_outcome, Some(RateOutcome::Inserted));
+
+        let expected_metric = get_test_purchase_rate_metric("key1", "1.3", 1);
+        let msg = QueryMsg::Metric {
+            key: "key1".to_string(),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        assert_eq!(from_binary::<Metric>(&resp).unwrap(), expected_metric);
+
+        // The typed messages are subject to the same validation as PostMetric
+        let msg = ExecuteMsg::PostPurchaseRate {
+            denom: STTOKEN_DENOM.to_string(),
+            purchase_rate: Decimal::from_str("1.3").unwrap(),
+            update_time: 1,
+            block_height: 1,
+            key: None,
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env, mock_info("non-poster", &[]), msg),
+            Err(ContractError::Unauthorized {})
+        );
+    }
+
+    #[test]
+    fn test_simulate_post_metric() {
+        let (mut deps, env, info) = default_instantiate();
+        let simulate = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                        sender: &str,
+                        metric: &Metric| {
+            let msg = QueryMsg::SimulatePostMetric {
+                sender: sender.to_string(),
+                metric: get_metric_update(metric),
+            };
+            let resp = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_binary::<SimulatePostMetricResponse>(&resp).unwrap()
+        };
+
+        // Simulate a valid post, which should not store the metric
+        let metric = get_test_redemption_rate_metric("key1", "1.0", 1);
+        let inserted = PostMetricResponse {
+            metric_outcome: MetricOutcome::Inserted,
+            rate_outcome: Some(RateOutcome::Inserted),
+        };
+        assert_eq!(
+            simulate(&deps, ADMIN_ADDRESS, &metric),
+            SimulatePostMetricResponse {
+                outcome: Some(inserted.clone()),
+                error: None,
+            }
+        );
+        let msg = QueryMsg::Metric {
+            key: "key1".to_string(),
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap_err();
+
+        // The simulated outcome should match the actual post
+        let msg = get_post_metric_msg(&metric);
+        let resp = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        let data: PostMetricResponse = from_binary(&resp.data.unwrap()).unwrap();
+        assert_eq!(data, inserted);
+
+        assert_eq!(
+            simulate(&deps, ADMIN_ADDRESS, &metric).outcome,
+            Some(PostMetricResponse {
+                metric_outcome: MetricOutcome::Replaced,
+                rate_outcome: Some(RateOutcome::Replaced),
+            })
+        );
+
+        // Failed validation should return the same error as the post
+        assert_eq!(
+            simulate(&deps, "non-poster", &metric),
+            SimulatePostMetricResponse {
+                outcome: None,
+                error: Some(ContractError::Unauthorized {}.to_string()),
+            }
+        );
+
+        let mut invalid_metric = get_test_redemption_rate_metric("key1", "1.0", 2);
+        invalid_metric.attributes = Some(to_binary("invalid").unwrap());
+        let expected_error = ContractError::InvalidMetricMetadataAttributes {
+            metric_type: MetricType::RedemptionRate,
+        };
+        assert_eq!(
+            simulate(&deps, ADMIN_ADDRESS, &invalid_metric).error,
+            Some(expected_error.to_string())
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&invalid_metric)
+            ),
+            Err(expected_error)
+        );
+
+        // Simulating a post that trips the circuit breaker should not freeze the denom
+        let msg = ExecuteMsg::SetRateDeviationLimit {
+            denom: STTOKEN_DENOM.to_string(),
+            limit: Some(RateDeviationLimit {
+                max_relative_deviation: Decimal::from_str("0.1").unwrap(),
+                scaling_period: None,
+                action: DeviationAction::CircuitBreaker,
+            }),
+        };
+        execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+
+        let metric = get_test_redemption_rate_metric("key1", "2.0", 2);
+        assert_eq!(
+            simulate(&deps, ADMIN_ADDRESS, &metric).outcome,
+            Some(PostMetricResponse {
+                metric_outcome: MetricOutcome::Held,
+                rate_outcome: Some(RateOutcome::CircuitBreakerTripped),
+            })
+        );
+
+        let resp = query(deps.as_ref(), env, QueryMsg::FreezeStatus {}).unwrap();
+        let freeze_status: FreezeStatusResponse = from_binary(&resp).unwrap();
+        assert!(freeze_status.frozen_denoms.is_empty());
+    }
+
+    #[test]
+    fn test_expected_latest_update_time() {
+        let (mut deps, env, info) = default_instantiate();
+        let mut post = |time: u64, expected_latest_update_time: Option<u64>| {
+            let metric = get_test_redemption_rate_metric("key1", "1.0", time);
+            let msg = ExecuteMsg::PostMetric {
+                key: metric.key,
+                value: metric.value,
+                metric_type: metric.metric_type,
+                update_time: metric.update_time,
+                block_height: metric.block_height,
+                attributes: metric.attributes,
+                expected_latest_update_time,
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), msg)
+        };
+
+        // If the key has never been posted, any expected time is a mismatch
+        let expected_error = ContractError::LatestUpdateTimeMismatch {
+            key: "key1".to_string(),
+            expected_latest_update_time: 1,
+            latest_update_time: None,
+        };
+        assert_eq!(
+            expected_error.to_string(),
+            "Metric key1 was posted with an expected latest update time of 1, but the latest update time is None"
+        );
+        assert_eq!(post(5, Some(1)), Err(expected_error));
+
+        // Posts without an expected time are not checked
+        post(5, None).unwrap();
+
+        // Posts with the correct expected time succeed
+        post(10, Some(5)).unwrap();
+        post(15, Some(10)).unwrap();
+
+        // A post that was meant to follow an earlier metric is rejected
+        assert_eq!(
+            post(12, Some(10)),
+            Err(ContractError::LatestUpdateTimeMismatch {
+                key: "key1".to_string(),
+                expected_latest_update_time: 10,
+                latest_update_time: Some(15),
+            })
+        );
+    }
+
+    #[test]
+    fn test_twap() {
+        let (mut deps, env, info) = default_instantiate();
+        let now = env.block.time.seconds();
+
+        // Post redemption and purchase rates 300, 200 and 100 seconds ago
+        for (value, time) in [("1.0", now - 300), ("2.0", now - 200), ("4.0", now - 100)] {
+            let metric = get_test_redemption_rate_metric("key1", value, time);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+            let metric = get_test_purchase_rate_metric("key2", value, time);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+        }
+        let params = |twap_window: u64| {
+            Some(
+                to_binary(&RateQueryParams {
+                    twap_window: Some(twap_window),
+                    ..Default::default()
+                })
+                .unwrap(),
+            )
+        };
+
+        // The TWAP over the last 200 seconds weights the latest two rates evenly
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: params(200),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let redemption_rate: RedemptionRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            redemption_rate.redemption_rate,
+            Decimal::from_str("3.0").unwrap()
+        );
+        assert_eq!(redemption_rate.update_time, now - 100);
+
+        // The TWAP over the last 100 seconds is the latest rate
+        let msg = QueryMsg::PurchaseRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: params(100),
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let purchase_rate: PurchaseRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(
+            purchase_rate.purchase_rate,
+            Decimal::from_str("4.0").unwrap()
+        );
+
+        // A window that extends beyond the retained history should fail
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: params(400),
+        };
+        assert_eq!(
+            query(deps.as_ref(), env.clone(), msg),
+            Err(ContractError::TwapWindowExceedsHistory {
+                denom: STTOKEN_DENOM.to_string(),
+                window: 400,
+                oldest_update_time: now - 300,
+            })
+        );
+
+        // An empty window should fail
+        let msg = QueryMsg::PurchaseRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: params(0),
+        };
+        assert_eq!(
+            query(deps.as_ref(), env, msg),
+            Err(ContractError::InvalidTwapWindow {})
+        );
+    }
+
+    #[test]
+    fn test_ema() {
+        let (mut deps, env, info) = default_instantiate();
+        let now = env.block.time.seconds();
+        let post = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>,
+                    value: &str,
+                    time: u64| {
+            let metric = get_test_redemption_rate_metric("key1", value, time);
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info(ADMIN_ADDRESS, &[]),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+        };
+        let ema_query = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Ema {
+                    key: "key1".to_string(),
+                },
+            )
+            .and_then(|resp| Ok(from_binary::<EmaResponse>(&resp)?))
+        };
+        let rate_ema_query = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier, Empty>| {
+            let params = RateQueryParams {
+                ema: true,
+                ..Default::default()
+            };
+            let msg = QueryMsg::RedemptionRate {
+                denom: STTOKEN_DENOM.to_string(),
+                params: Some(to_binary(&params).unwrap()),
+            };
+            query(deps.as_ref(), mock_env(), msg)
+                .map(|resp| from_binary::<RedemptionRateResponse>(&resp).unwrap())
+        };
+
+        // A zero half-life is invalid
+        let msg = ExecuteMsg::SetEmaHalfLife {
+            key: "key1".to_string(),
+            half_life: Some(0),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env.clone(), info.clone(), msg),
+            Err(ContractError::InvalidEmaHalfLife {})
+        );
+
+        // Set a half-life of 100 seconds and post two rates, one half-life apart
+        let msg = ExecuteMsg::SetEmaHalfLife {
+            key: "key1".to_string(),
+            half_life: Some(100),
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+
+        post(&mut deps, "1.0", now - 200);
+        post(&mut deps, "2.0", now - 100);
+
+        let expected_ema = EmaResponse {
+            value: Decimal::from_str("1.5").unwrap(),
+            update_time: now - 100,
+            half_life: 100,
+        };
+        assert_eq!(ema_query(&deps).unwrap(), expected_ema);
+
+        let redemption_rate = rate_ema_query(&deps).unwrap();
+        assert_eq!(
+            redemption_rate.redemption_rate,
+            Decimal::from_str("1.5").unwrap()
+        );
+        assert_eq!(redemption_rate.update_time, now - 100);
+
+        // Replacing the latest metric re-applies the new value to the prior averages,
+        // so the replaced value is dropped
+        post(&mut deps, "3.0", now - 100);
+        assert_eq!(
+            ema_query(&deps).unwrap().value,
+            Decimal::from_str("2.0").unwrap()
+        );
+        assert_eq!(
+            rate_ema_query(&deps).unwrap().redemption_rate,
+            Decimal::from_str("2.0").unwrap()
+        );
+
+        post(&mut deps, "2.0", now - 100);
+        assert_eq!(ema_query(&deps).unwrap(), expected_ema);
+
+        // A backfilled metric does not change the average
+        post(&mut deps, "9.0", now - 150);
+        assert_eq!(ema_query(&deps).unwrap(), expected_ema);
+
+        // Nor is the backfilled metric picked up when the latest metric is replaced afterwards
+        post(&mut deps, "3.0", now - 100);
+        assert_eq!(
+            ema_query(&deps).unwrap().value,
+            Decimal::from_str("2.0").unwrap()
+        );
+        assert_eq!(
+            rate_ema_query(&deps).unwrap().redemption_rate,
+            Decimal::from_str("2.0").unwrap()
+        );
+
+        post(&mut deps, "2.0", now - 100);
+        assert_eq!(ema_query(&deps).unwrap(), expected_ema);
+
+        // The EMA cannot be combined with a TWAP
+        let params = RateQueryParams {
+            twap_window: Some(100),
+            ema: true,
+            ..Default::default()
+        };
+        let msg = QueryMsg::RedemptionRate {
+            denom: STTOKEN_DENOM.to_string(),
+            params: Some(to_binary(&params).unwrap()),
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap_err();
+
+        // Removing the latest metric rebuilds the averages from the remaining history
+        let msg = SudoMsg::RemoveMetric {
+            key: "key1".to_string(),
+            update_time: Some(now - 100),
+        };
+        sudo(deps.as_mut(), env.clone(), msg).unwrap();
+
+        let remaining_ema = ema_query(&deps).unwrap();
+        assert_eq!(remaining_ema.update_time, now - 150);
+        assert_eq!(
+            rate_ema_query(&deps).unwrap().redemption_rate,
+            remaining_ema.value
+        );
+
+        // Removing the half-life removes the key's average, and the rate average is
+        // no longer available once a new rate is posted
+        let msg = ExecuteMsg::SetEmaHalfLife {
+            key: "key1".to_string(),
+            half_life: None,
+        };
+        execute(deps.as_mut(), env, info, msg).unwrap();
+        ema_query(&deps).unwrap_err();
+
+        post(&mut deps, "2.0", now);
+        assert_eq!(
+            rate_ema_query(&deps),
+            Err(ContractError::EmaUnavailable {
+                denom: STTOKEN_DENOM.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_metric_stats() {
+        let (mut deps, env, info) = default_instantiate();
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        // Post four numeric values and one non-numeric value
+        for (value, time) in [("3", 1), ("1", 2), ("value", 3), ("4", 4), ("2", 5)] {
+            let metric = Metric {
+                key: "key1".to_string(),
+                value: value.to_string(),
+                metric_type: MetricType::Other("other".to_string()),
+                update_time: time,
+                block_height: time,
+                attributes: None,
+                received_time: None,
+                received_height: None,
+            };
+            let msg = get_post_metric_msg(&metric);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+        }
+        let stats = |since: Option<u64>, until: Option<u64>| {
+            let msg = QueryMsg::MetricStats {
+                key: "key1".to_string(),
+                since,
+                until,
+            };
+            query(deps.as_ref(), env.clone(), msg)
+                .map(|resp| from_binary::<MetricStatsResponse>(&resp).unwrap())
+        };
+
+        // Stats over the full history skip the non-numeric value
+        let full_stats = stats(None, None).unwrap();
+        assert_eq!(full_stats.count, 4);
+        assert_eq!(full_stats.start_time, 1);
+        assert_eq!(full_stats.end_time, 5);
+        assert_eq!(full_stats.min, dec("1"));
+        assert_eq!(full_stats.max, dec("4"));
+        assert_eq!(full_stats.mean, dec("2.5"));
+        assert_eq!(full_stats.median, dec("2.5"));
+        assert!(full_stats.std_dev.abs_diff(dec("1.118033988749894848")) < dec("0.000000001"));
+
+        // Stats over a time range only include the values within the range
+        assert_eq!(
+            stats(Some(2), Some(4)).unwrap(),
+            MetricStatsResponse {
+                count: 2,
+                start_time: 2,
+                end_time: 4,
+                min: dec("1"),
+                max: dec("4"),
+                mean: dec("2.5"),
+                median: dec("2.5"),
+                std_dev: dec("1.5"),
+            }
+        );
+        assert_eq!(stats(Some(4), None).unwrap().median, dec("3"));
+
+        // A range without any numeric values should fail
+        assert_eq!(
+            stats(Some(3), Some(3)),
+            Err(ContractError::NoMetricValues {
+                key: "key1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_point_in_time_queries() {
+        let (mut deps, env, info) = default_instantiate();
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        // Post redemption and purchase rates at times 10, 20 and 40
+        for (value, time) in [("1.0", 10), ("2.0", 20), ("1.0", 40)] {
+            let metric = get_test_redemption_rate_metric("key1", value, time);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+            let metric = get_test_purchase_rate_metric("key2", value, time);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+        }
+        let redemption_rate_at = |time: u64, interpolate: bool| {
+            let msg = QueryMsg::RedemptionRateAt {
+                denom: STTOKEN_DENOM.to_string(),
+                time,
+                interpolate,
+            };
+            query(deps.as_ref(), env.clone(), msg)
+                .map(|resp| from_binary::<RedemptionRateResponse>(&resp).unwrap())
+        };
+
+        // Without interpolation, the latest rate at or before the time is returned
+        let redemption_rate = redemption_rate_at(30, false).unwrap();
+        assert_eq!(redemption_rate.redemption_rate, dec("2.0"));
+        assert_eq!(redemption_rate.update_time, 20);
+        assert_eq!(redemption_rate_at(40, false).unwrap().update_time, 40);
+        assert_eq!(redemption_rate_at(100, false).unwrap().update_time, 40);
+
+        // With interpolation, the rate is weighted by the distance to each neighbor
+        let redemption_rate = redemption_rate_at(15, true).unwrap();
+        assert_eq!(redemption_rate.redemption_rate, dec("1.5"));
+        assert_eq!(redemption_rate.update_time, 10);
+        assert_eq!(
+            redemption_rate_at(35, true).unwrap().redemption_rate,
+            dec("1.25")
+        );
+        assert_eq!(
+            redemption_rate_at(20, true).unwrap().redemption_rate,
+            dec("2.0")
+        );
+        assert_eq!(
+            redemption_rate_at(100, true).unwrap().redemption_rate,
+            dec("1.0")
+        );
+
+        let msg = QueryMsg::PurchaseRateAt {
+            denom: STTOKEN_DENOM.to_string(),
+            time: 30,
+            interpolate: true,
+        };
+        let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let purchase_rate: PurchaseRateResponse = from_binary(&resp).unwrap();
+        assert_eq!(purchase_rate.purchase_rate, dec("1.5"));
+        assert_eq!(purchase_rate.update_time, 20);
+
+        // Times before the retained history should fail
+        assert_eq!(
+            redemption_rate_at(5, false),
+            Err(ContractError::TimePredatesHistory {
+                time: 5,
+                oldest_update_time: 10,
+            })
+        );
+
+        // The metric query returns the metric in effect, with an interpolated value if requested
+        let metric_at = |time: u64, interpolate: bool| {
+            let msg = QueryMsg::MetricAt {
+                key: "key1".to_string(),
+                time,
+                interpolate,
+            };
+            query(deps.as_ref(), env.clone(), msg).map(|resp| from_binary::<Metric>(&resp).unwrap())
+        };
+        assert_eq!(
+            metric_at(30, false).unwrap(),
+            get_test_redemption_rate_metric("key1", "2.0", 20)
+        );
+        assert_eq!(
+            metric_at(30, true).unwrap(),
+            get_test_redemption_rate_metric("key1", "1.5", 20)
+        );
+    }
+
+    #[test]
+    fn test_historical_pagination() {
+        let (mut deps, env, info) = default_instantiate();
+
+        // Post 60 redemption rates, which is more than fits on a single page
+        for time in 1..=60 {
+            let metric = get_test_redemption_rate_metric("key1", "1.0", time);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                get_post_metric_msg(&metric),
+            )
+            .unwrap();
+        }
+        let historical_rates = |start_time, end_time, start_after, order, limit| {
+            let msg = QueryMsg::HistoricalRedemptionRates {
+                denom: STTOKEN_DENOM.to_string(),
+                params: None,
+                start_time,
+                end_time,
+                start_after,
+                order,
+                limit,
+            };
+            let resp = query(deps.as_ref(), env.clone(), msg).unwrap();
+            from_binary::<RedemptionRates>(&resp)
+                .unwrap()
+                .redemption_rates
+                .into_iter()
+                .map(|rate| rate.update_time)
+                .collect::<Vec<_>>()
+        };
+
+        // The page size is capped, with the newest entries returned first by default
+        let times = historical_rates(None, None, None, None, Some(1000));
+        assert_eq!(times, (11..=60).rev().collect::<Vec<_>>());
+        let times = historical_rates(None, None, None, None, None);
+        assert_eq!(times.len(), 50);
+
+        // Walk the full history from oldest to newest, using the cursor
+        let mut all_times = vec![];
+        let mut start_after = None;
+        loop {
+            let times = historical_rates(
+                None,
+                None,
+                start_after,
+                Some(HistoryOrder::Ascending),
+                Some(25),
+            );
+            if times.is_empty() {
+                break;
+            }
+            start_after = times.last().copied();
+            all_times.extend(times);
+        }
+        assert_eq!(all_times, (1..=60).collect::<Vec<_>>());
+
+        // Filter by time range, walking backwards from the end of the range
+        let times = historical_rates(Some(10), Some(20), None, None, Some(5));
+        assert_eq!(times, vec![20, 19, 18, 17, 16]);
+        let times = historical_rates(Some(10), Some(20), Some(16), None, Some(10));
+        assert_eq!(times, vec![15, 14, 13, 12, 11, 10]);
+
+        // The same pagination applies to the metric history
+        let msg = QueryMsg::HistoricalMetrics {
+            key: "key1".to_string(),
+            start_time: Some(30),
+            end_time: None,
+            start_after: Some(57),
+            order: Some(HistoryOrder::Ascending),
+            limit: None,
+        };
+        let resp = query(deps.as_ref(), env, msg).unwrap();
+        let metrics: Metrics = from_binary(&resp).unwrap();
+        let times: Vec<u64> = metrics.metrics.iter().map(|m| m.update_time).collect();
+        assert_eq!(times, vec![58, 59, 60]);
+    }
 }
@@ -1,6 +1,6 @@
-use crate::state::MetricType;
+use crate::state::{BoundsTarget, MetricBounds, MetricType, Role};
 
-use cosmwasm_std::StdError;
+use cosmwasm_std::{Decimal, StdError};
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
@@ -11,12 +11,57 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    #[error("There is no pending admin handover")]
+    NoPendingAdmin {},
+
+    #[error("Pending config change {id} not found")]
+    PendingChangeNotFound { id: u64 },
+
+    #[error("Pending config change {id} cannot be executed until {execute_after}")]
+    TimelockNotExpired { id: u64, execute_after: u64 },
+
+    #[error(
+        "Timelock of {timelock_seconds} seconds exceeds the max of {max_timelock_seconds} seconds"
+    )]
+    TimelockTooLong {
+        timelock_seconds: u64,
+        max_timelock_seconds: u64,
+    },
+
+    #[error("The contract is paused")]
+    Paused {},
+
+    #[error("Metric key {key} is frozen")]
+    KeyFrozen { key: String },
+
+    #[error("Denom {denom} is frozen")]
+    DenomFrozen { denom: String },
+
+    #[error("Denom {denom} has a rate update held by the circuit breaker")]
+    DenomHeld { denom: String },
+
+    #[error("Address {address} does not have the {role} role")]
+    RoleNotFound { role: Role, address: String },
+
+    #[error("Poster {poster} is not permitted to post metric {key} (type {metric_type:?})")]
+    PosterScopeViolation {
+        poster: String,
+        key: String,
+        metric_type: MetricType,
+    },
+
+    #[error("Poster {poster} is not permitted to post rates for denom {denom}")]
+    PosterDenomViolation { poster: String, denom: String },
+
     #[error("The provided metric (type {metric_type:?}) does not contain required attributes")]
     MissingMetricMetadataAttributes { metric_type: MetricType },
 
     #[error("The provided metric (type {metric_type:?}) has invalid metadata attributes")]
     InvalidMetricMetadataAttributes { metric_type: MetricType },
 
+    #[error("Metric {key} not found")]
+    MetricNotFound { key: String },
+
     #[error("Invalid denom: {reason}")]
     InvalidDenom { reason: String },
 
@@ -29,6 +74,126 @@ pub enum ContractError {
     #[error("The denom for the redemption rate metric must not be an IBC denom, {denom} provided")]
     InvalidRedemptionRateDenom { denom: String },
 
+    #[error("The rate for {denom} moved from {previous} to {new}, exceeding the max deviation of {max_deviation}")]
+    RateDeviationExceeded {
+        denom: String,
+        previous: Decimal,
+        new: Decimal,
+        max_deviation: Decimal,
+    },
+
+    #[error("Invalid metric bounds: {bounds}, min must not exceed max")]
+    InvalidMetricBounds { bounds: MetricBounds },
+
+    #[error("The value {value} for {target} is outside of the bounds {bounds}")]
+    MetricOutOfBounds {
+        target: BoundsTarget,
+        value: Decimal,
+        bounds: MetricBounds,
+    },
+
+    #[error("The redemption rate for {denom} decreased from {previous} to {new}, but the update was not flagged as a slash")]
+    RedemptionRateDecrease {
+        denom: String,
+        previous: Decimal,
+        new: Decimal,
+    },
+
+    #[error("The redemption rate for {denom} of {new} at {update_time} exceeds the following rate of {next} at {next_update_time}, which would become a decrease")]
+    RedemptionRateExceedsNext {
+        denom: String,
+        new: Decimal,
+        update_time: u64,
+        next: Decimal,
+        next_update_time: u64,
+    },
+
+    #[error("The rate for {denom} was last updated at {update_time}, which exceeds the max age of {max_age} seconds (current time: {current_time})")]
+    StaleRate {
+        denom: String,
+        update_time: u64,
+        current_time: u64,
+        max_age: u64,
+    },
+
+    #[error("Metric {key} has an update time of {update_time}, which is later than the max allowed time of {max_time}")]
+    MetricTimestampInFuture {
+        key: String,
+        update_time: u64,
+        max_time: u64,
+    },
+
+    #[error("Metric {key} has an update time of {update_time}, which is earlier than the min allowed time of {min_time}")]
+    MetricTooOld {
+        key: String,
+        update_time: u64,
+        min_time: u64,
+    },
+
+    #[error("Metric {key} (time {update_time}, height {block_height}) is not newer than the latest metric (time {latest_update_time}, height {latest_block_height})")]
+    MetricNotNewer {
+        key: String,
+        update_time: u64,
+        block_height: u64,
+        latest_update_time: u64,
+        latest_block_height: u64,
+    },
+
+    #[error("Metric {key} at time {update_time} has height {block_height}, but a metric with a greater height ({existing_block_height}) already exists")]
+    MetricSuperseded {
+        key: String,
+        update_time: u64,
+        block_height: u64,
+        existing_block_height: u64,
+    },
+
+    #[error(
+        "Metric {key} was posted with an expected latest update time of {expected_latest_update_time}, but the latest update time is {}",
+        .latest_update_time.map_or("None".to_string(), |time| time.to_string())
+    )]
+    LatestUpdateTimeMismatch {
+        key: String,
+        expected_latest_update_time: u64,
+        latest_update_time: Option<u64>,
+    },
+
+    #[error("Metric {key} at time {update_time} conflicts with an existing metric")]
+    ConflictingMetric { key: String, update_time: u64 },
+
+    #[error("A metric batch must contain at least one metric")]
+    EmptyBatch {},
+
+    #[error("Max batch size must be greater than zero")]
+    InvalidMaxBatchSize {},
+
+    #[error("Batch of {size} metrics exceeds the max batch size of {max_batch_size}")]
+    BatchTooLarge { size: u64, max_batch_size: u64 },
+
+    #[error("Time {time} predates the retained history, which starts at {oldest_update_time}")]
+    TimePredatesHistory { time: u64, oldest_update_time: u64 },
+
+    #[error("Metric {key} has no numeric values in the requested time range")]
+    NoMetricValues { key: String },
+
+    #[error("EMA half-life must be greater than zero")]
+    InvalidEmaHalfLife {},
+
+    #[error("No up-to-date moving average is available for {denom}")]
+    EmaUnavailable { denom: String },
+
+    #[error("TWAP window must be greater than zero")]
+    InvalidTwapWindow {},
+
+    #[error("TWAP window of {window} seconds for {denom} exceeds the retained history, which starts at {oldest_update_time}")]
+    TwapWindowExceedsHistory {
+        denom: String,
+        window: u64,
+        oldest_update_time: u64,
+    },
+
+    #[error("No pending rate update found for {denom}")]
+    PendingUpdateNotFound { denom: String },
+
     #[error("Cannot upgrade to a different contract")]
     InvalidContract {},
 
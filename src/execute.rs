@@ -1,12 +1,55 @@
 use crate::error::ContractError;
+use crate::helpers::{
+    ensure_denom_not_frozen, ensure_not_paused, ensure_scope_permits_denom, ensure_within_bounds,
+    relative_deviation, update_ema, validate_channel_id, validate_timelock,
+};
+use crate::msg::{
+    MetricOutcome, MetricUpdate, PostMetricResponse, PostMetricsResponse, RateOutcome,
+};
 use crate::state::{
-    History, Metric, MetricType, RedemptionRate, RedemptionRateAttributes, 
-    PurchaseRate, PurchaseRateAttributes, CONFIG, METRICS,
-    REDEMPTION_RATES, PURCHASE_RATES,
+    BoundsTarget, Config, ConfigChange, DeviationAction, DuplicatePolicy, Ema, History,
+    InsertOrdering, InsertOutcome, InsertPolicy, Metric, MetricBounds, MetricType, PendingChange,
+    PendingRateUpdate, PosterScope, PurchaseRate, PurchaseRateAttributes, Rate, RateDeviationLimit,
+    RateUpdate, RedemptionRate, RedemptionRateAttributes, Role, SlashEvent, CONFIG, DENOM_BOUNDS,
+    EMAS, EMA_HALF_LIVES, FROZEN_DENOMS, FROZEN_KEYS, HEARTBEATS, INSERT_POLICIES, KEY_BOUNDS,
+    METRICS, MONOTONIC_DENOMS, PAUSED, PENDING_CHANGES, PENDING_CHANGE_ID, PENDING_RATE_UPDATES,
+    POSTERS, POSTER_SCOPES, PURCHASE_RATES, PURCHASE_RATE_EMAS, RATE_DEVIATION_LIMITS,
+    RATE_MAX_AGES, REDEMPTION_RATES, REDEMPTION_RATE_EMAS, SLASH_EVENTS,
 };
-use cosmwasm_std::{ensure, from_binary, Binary, Decimal, DepsMut, MessageInfo, Response};
+use cosmwasm_schema::serde::{de::DeserializeOwned, Serialize};
+use cosmwasm_std::{
+    ensure, from_binary, to_binary, Addr, Binary, Decimal, Deps, DepsMut, Empty, Env, Event,
+    MessageInfo, Response, Storage,
+};
+use cw_storage_plus::Map;
 use std::str::FromStr;
 
+// The max number of metrics in a PostMetrics batch, if not configured by the admin
+const DEFAULT_MAX_BATCH_SIZE: u64 = 20;
+
+/// Loads the config and confirms the sender is the admin
+pub fn ensure_admin(storage: &dyn Storage, sender: &Addr) -> Result<Config, ContractError> {
+    let config = CONFIG.load(storage)?;
+    ensure!(
+        *sender == config.admin_address,
+        ContractError::Unauthorized {}
+    );
+    Ok(config)
+}
+
+/// Loads the config and confirms the sender is either the admin or the guardian
+pub fn ensure_admin_or_guardian(
+    storage: &dyn Storage,
+    sender: &Addr,
+) -> Result<Config, ContractError> {
+    let config = CONFIG.load(storage)?;
+    ensure!(
+        *sender == config.admin_address || Some(sender) == config.guardian_address.as_ref(),
+        ContractError::Unauthorized {}
+    );
+    Ok(config)
+}
+
 /// Stores a given metric passed via an ICA from a source chain
 /// The oracle stores each metric generically with key and value attributes
 ///
@@ -19,6 +62,7 @@ use std::str::FromStr;
 #[allow(clippy::too_many_arguments)]
 pub fn post_metric(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
     key: String,
     value: String,
@@ -26,14 +70,290 @@ pub fn post_metric(
     update_time: u64,
     block_height: u64,
     attributes: Option<Binary>,
+    expected_latest_update_time: Option<u64>,
+) -> Result<Response, ContractError> {
+    let metric = MetricUpdate {
+        key,
+        value,
+        metric_type,
+        update_time,
+        block_height,
+        attributes,
+        expected_latest_update_time,
+    };
+    post_single_metric(deps, &env, &info.sender, "post_metric", metric)
+}
+
+/// Stores a redemption rate passed via an ICA from a source chain
+/// The rate is stored as a redemption rate metric, exactly as if it was posted with PostMetric
+/// If the key is not specified, it defaults to `{denom}_redemption_rate`
+#[allow(clippy::too_many_arguments)]
+pub fn post_redemption_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    redemption_rate: Decimal,
+    update_time: u64,
+    block_height: u64,
+    key: Option<String>,
+    slash: bool,
+) -> Result<Response, ContractError> {
+    let metric_type = MetricType::RedemptionRate;
+    let attributes = RedemptionRateAttributes {
+        sttoken_denom: denom.clone(),
+        slash,
+    };
+    let metric = MetricUpdate {
+        key: key.unwrap_or_else(|| format!("{denom}_{metric_type}")),
+        value: redemption_rate.to_string(),
+        metric_type,
+        update_time,
+        block_height,
+        attributes: Some(to_binary(&attributes)?),
+        expected_latest_update_time: None,
+    };
+    post_single_metric(deps, &env, &info.sender, "post_redemption_rate", metric)
+}
+
+/// Stores a purchase rate passed via an ICA from a source chain
+/// The rate is stored as a purchase rate metric, exactly as if it was posted with PostMetric
+/// If the key is not specified, it defaults to `{denom}_purchase_rate`
+#[allow(clippy::too_many_arguments)]
+pub fn post_purchase_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    purchase_rate: Decimal,
+    update_time: u64,
+    block_height: u64,
+    key: Option<String>,
 ) -> Result<Response, ContractError> {
-    // Only the ICA account can post metrics
+    let metric_type = MetricType::PurchaseRate;
+    let attributes = PurchaseRateAttributes {
+        sttoken_denom: denom.clone(),
+    };
+    let metric = MetricUpdate {
+        key: key.unwrap_or_else(|| format!("{denom}_{metric_type}")),
+        value: purchase_rate.to_string(),
+        metric_type,
+        update_time,
+        block_height,
+        attributes: Some(to_binary(&attributes)?),
+        expected_latest_update_time: None,
+    };
+    post_single_metric(deps, &env, &info.sender, "post_purchase_rate", metric)
+}
+
+// Stores a single metric and builds the response, with the outcome set as the response data
+fn post_single_metric(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    action: &str,
+    metric: MetricUpdate,
+) -> Result<Response, ContractError> {
+    let (metric_response, data) = store_metric(deps, env, sender, metric)?;
+
+    Ok(Response::new()
+        .add_attribute("action", action)
+        .add_attributes(metric_response.attributes)
+        .add_events(metric_response.events)
+        .set_data(to_binary(&data)?))
+}
+
+/// Stores a batch of metrics passed via an ICA from a source chain
+/// Each metric is stored with the same logic as PostMetric, and the batch is rejected
+/// entirely if any one of the metrics is rejected
+/// The attributes of each metric are emitted under a separate "post_metric" event
+pub fn post_metrics(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    metrics: Vec<MetricUpdate>,
+) -> Result<Response, ContractError> {
+    // Confirm the batch is non-empty and within the max size
     let config = CONFIG.load(deps.storage)?;
+    let size = metrics.len() as u64;
+    let max_batch_size = config.max_batch_size.unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+    ensure!(size > 0, ContractError::EmptyBatch {});
     ensure!(
-        info.sender == config.admin_address,
+        size <= max_batch_size,
+        ContractError::BatchTooLarge {
+            size,
+            max_batch_size
+        }
+    );
+
+    let mut response = Response::new()
+        .add_attribute("action", "post_metrics")
+        .add_attribute("batch_size", size.to_string());
+    let mut outcomes = vec![];
+    for metric in metrics {
+        let (metric_response, outcome) = store_metric(deps.branch(), &env, &info.sender, metric)?;
+        response = response
+            .add_event(Event::new("post_metric").add_attributes(metric_response.attributes))
+            .add_events(metric_response.events);
+        outcomes.push(outcome);
+    }
+
+    Ok(response.set_data(to_binary(&PostMetricsResponse { outcomes })?))
+}
+
+// Validates and stores a single metric, along with the derived redemption or purchase rate
+// Returns the metric's attributes and events, as well as the outcome of the post
+fn store_metric(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    metric: MetricUpdate,
+) -> Result<(Response, PostMetricResponse), ContractError> {
+    let prepared_metric = prepare_metric(deps.as_ref(), env, sender, metric)?;
+    commit_metric(deps.storage, prepared_metric)
+}
+
+/// Runs the full validation of a metric post from the given sender, without storing anything
+/// Returns the outcome that the post would have, or the error that it would fail with
+pub fn simulate_post_metric(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    metric: MetricUpdate,
+) -> Result<PostMetricResponse, ContractError> {
+    let prepared_metric = prepare_metric(deps, env, sender, metric)?;
+    Ok(prepared_metric.outcome())
+}
+
+// A metric that has passed validation, along with the changes required to store it
+struct PreparedMetric {
+    metric: Metric,
+    metric_history: History<Metric>,
+    metric_outcome: InsertOutcome,
+    rate: Option<PreparedRate>,
+    slash_event: Option<SlashEvent>,
+    ema: Option<Ema>,
+    rate_ema: Option<Ema>,
+}
+
+impl PreparedMetric {
+    // Returns the outcome of the post that's returned in the response
+    // If the metric's rate tripped the circuit breaker, the metric is reported as held
+    fn outcome(&self) -> PostMetricResponse {
+        let metric_outcome = match self.rate {
+            Some(PreparedRate::Trip(_)) => MetricOutcome::Held,
+            _ => self.metric_outcome.clone().into(),
+        };
+        PostMetricResponse {
+            metric_outcome,
+            rate_outcome: self.rate.as_ref().map(PreparedRate::outcome),
+        }
+    }
+}
+
+// The change to the redemption or purchase rate store that results from a new rate
+enum PreparedRate {
+    Add(RateUpdate, InsertOutcome),
+    Quarantine,
+    Trip(Box<PendingRateUpdate>),
+}
+
+impl PreparedRate {
+    // Converts the change into the outcome that's returned in the response
+    fn outcome(&self) -> RateOutcome {
+        match self {
+            PreparedRate::Add(_, outcome) => outcome.clone().into(),
+            PreparedRate::Quarantine => RateOutcome::Quarantined,
+            PreparedRate::Trip(_) => RateOutcome::CircuitBreakerTripped,
+        }
+    }
+
+    // Returns true if the rate will be written to its store
+    fn is_stored(&self) -> bool {
+        matches!(
+            self,
+            PreparedRate::Add(_, InsertOutcome::Inserted | InsertOutcome::Replaced)
+        )
+    }
+}
+
+// Validates a metric and determines how it, and any derived rate, would be stored
+// Nothing is written, so that the same validation can be used to simulate a post
+fn prepare_metric(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    metric: MetricUpdate,
+) -> Result<PreparedMetric, ContractError> {
+    let MetricUpdate {
+        key,
+        value,
+        metric_type,
+        update_time,
+        block_height,
+        attributes,
+        expected_latest_update_time,
+    } = metric;
+
+    // Only addresses with the poster role can post metrics
+    ensure!(
+        POSTERS.has(deps.storage, sender),
         ContractError::Unauthorized {}
     );
 
+    // If the poster has a scope, confirm the metric falls within it
+    // The denom of a rate is checked against the scope once the attributes are decoded
+    let scope = POSTER_SCOPES.may_load(deps.storage, sender)?;
+    if let Some(scope) = &scope {
+        ensure!(
+            scope.permits(&key, &metric_type),
+            ContractError::PosterScopeViolation {
+                poster: sender.to_string(),
+                key,
+                metric_type,
+            }
+        );
+    }
+
+    // Confirm the contract is not paused and the key has not been frozen
+    ensure_not_paused(deps.storage)?;
+    ensure!(
+        !FROZEN_KEYS.has(deps.storage, &key),
+        ContractError::KeyFrozen { key }
+    );
+
+    // Confirm the update time is within the configured distance of the current block time
+    // This prevents a metric with a far future timestamp from permanently becoming the latest
+    let config = CONFIG.load(deps.storage)?;
+    let current_time = env.block.time.seconds();
+    if let Some(max_clock_skew) = config.max_clock_skew {
+        let max_time = current_time.saturating_add(max_clock_skew);
+        ensure!(
+            update_time <= max_time,
+            ContractError::MetricTimestampInFuture {
+                key,
+                update_time,
+                max_time,
+            }
+        );
+    }
+    if let Some(max_metric_age) = config.max_metric_age {
+        let min_time = current_time.saturating_sub(max_metric_age);
+        ensure!(
+            update_time >= min_time,
+            ContractError::MetricTooOld {
+                key,
+                update_time,
+                min_time,
+            }
+        );
+    }
+
+    // If the value is numeric, confirm it falls within any bounds configured for the key
+    if let Ok(decimal_value) = Decimal::from_str(&value) {
+        ensure_within_bounds(deps.storage, BoundsTarget::Key(key.clone()), decimal_value)?;
+    }
+
     // Build the new metric object
     let new_metric = Metric {
         key: key.clone(),
@@ -42,20 +362,37 @@ pub fn post_metric(
         update_time,
         block_height,
         attributes: attributes.clone(),
+        received_time: Some(env.block.time.seconds()),
+        received_height: Some(env.block.height),
     };
 
-    // Add the metric to the store
-    // If a duplicate metric is added, it will replace the existing one
+    // Add the metric to the history, after checking it against the key's insert policy
+    // If a duplicate metric is added, it will replace the existing one (if permitted by the policy)
     // If the list is at capacity and this metric is older than the oldest one in the list,
     // it will not be added
-    let mut metric_history = match METRICS.may_load(deps.storage, &key)? {
-        Some(history) => history,
-        None => History::<Metric>::default(),
-    };
-    metric_history.add(new_metric.clone());
-    METRICS.save(deps.storage, &key, &metric_history)?;
+    let mut metric_history: History<Metric> =
+        METRICS.may_load(deps.storage, &key)?.unwrap_or_default();
+
+    // If the poster specified the latest update time they expect, confirm it matches the store
+    // This prevents a reordered post from overwriting a post that it was meant to precede
+    if let Some(expected_latest_update_time) = expected_latest_update_time {
+        let latest_update_time = metric_history.get_latest().map(|latest| latest.update_time);
+        ensure!(
+            latest_update_time == Some(expected_latest_update_time),
+            ContractError::LatestUpdateTimeMismatch {
+                key,
+                expected_latest_update_time,
+                latest_update_time,
+            }
+        );
+    }
+
+    ensure_insert_permitted(deps.storage, &metric_history, &new_metric)?;
+    let metric_outcome = metric_history.add(new_metric.clone());
 
     // Parse the metric_type field and handle any other metric-type specific cases
+    let mut prepared_rate = None;
+    let mut slash_event = None;
     match metric_type {
         // If the metric is a redemption rate update, add record to the redemption rate store
         MetricType::RedemptionRate => {
@@ -73,6 +410,8 @@ pub fn post_metric(
             };
 
             let sttoken_denom = attributes.sttoken_denom.clone();
+            ensure_scope_permits_denom(scope.as_ref(), sender, &sttoken_denom)?;
+            ensure_denom_not_frozen(deps.storage, &sttoken_denom)?;
 
             // the contract is deployed on the same chain
             // // Get the transfer channel ID between the oracle and controller chain,
@@ -90,19 +429,84 @@ pub fn post_metric(
 
             // Store the redemption rate in the redemption rate table
             let redemption_rate_value = Decimal::from_str(&new_metric.value)?;
+            ensure_within_bounds(
+                deps.storage,
+                BoundsTarget::Denom(sttoken_denom.clone()),
+                redemption_rate_value,
+            )?;
             let new_redemption_rate = RedemptionRate {
                 denom: sttoken_denom.clone(),
                 redemption_rate: redemption_rate_value,
                 update_time: new_metric.update_time,
+                received_time: new_metric.received_time,
+                received_height: new_metric.received_height,
             };
 
-            let mut redemption_rate_history =
-                match REDEMPTION_RATES.may_load(deps.storage, &sttoken_denom)? {
-                    Some(history) => history,
-                    None => History::<RedemptionRate>::default(),
+            // Check whether this update lowers the redemption rate in effect at its update time
+            // If the denom is monotonic, a decrease is only permitted if flagged as a slash
+            let rate_history = REDEMPTION_RATES
+                .may_load(deps.storage, &sttoken_denom)?
+                .unwrap_or_default();
+            let monotonic = MONOTONIC_DENOMS.has(deps.storage, &sttoken_denom);
+            let decreased_from = rate_history
+                .get_at_or_before(update_time)
+                .map(|previous| previous.redemption_rate)
+                .filter(|previous| redemption_rate_value < *previous);
+            if let Some(previous) = decreased_from {
+                ensure!(
+                    attributes.slash || !monotonic,
+                    ContractError::RedemptionRateDecrease {
+                        denom: sttoken_denom,
+                        previous,
+                        new: redemption_rate_value,
+                    }
+                );
+            }
+
+            // If the update is backfilled on a monotonic denom, it also can't exceed the following
+            // rate, since that rate would become a decrease (unless it was recorded as a slash)
+            if let (true, Some(next)) = (monotonic, rate_history.get_after(update_time)) {
+                // Slash events outlive removed rates, so the event must match the next rate
+                let next_is_slash = SLASH_EVENTS
+                    .may_load(deps.storage, (&sttoken_denom, next.update_time))?
+                    .filter(|slash_event| slash_event.redemption_rate == next.redemption_rate)
+                    .is_some();
+                ensure!(
+                    redemption_rate_value <= next.redemption_rate || next_is_slash,
+                    ContractError::RedemptionRateExceedsNext {
+                        denom: sttoken_denom,
+                        new: redemption_rate_value,
+                        update_time,
+                        next: next.redemption_rate,
+                        next_update_time: next.update_time,
+                    }
+                );
+            }
+
+            let mut rate = prepare_rate(
+                deps.storage,
+                &REDEMPTION_RATES,
+                &new_metric,
+                new_redemption_rate,
+            )?;
+
+            // Record the decrease as a slash event if it was flagged as a slash
+            // If the rate is held by the circuit breaker, the event is recorded once it's approved
+            if let (Some(previous), true) = (decreased_from, attributes.slash) {
+                let event = SlashEvent {
+                    denom: sttoken_denom,
+                    previous_redemption_rate: previous,
+                    redemption_rate: redemption_rate_value,
+                    update_time: new_metric.update_time,
+                    block_height: new_metric.block_height,
                 };
-            redemption_rate_history.add(new_redemption_rate);
-            REDEMPTION_RATES.save(deps.storage, &sttoken_denom, &redemption_rate_history)?;
+                match &mut rate {
+                    PreparedRate::Trip(pending_update) => pending_update.slash_event = Some(event),
+                    rate if rate.is_stored() => slash_event = Some(event),
+                    _ => {}
+                }
+            }
+            prepared_rate = Some(rate);
         }
         MetricType::PurchaseRate => {
             // Deserialize the metric attributes to get the denom and base denom
@@ -119,26 +523,87 @@ pub fn post_metric(
             };
 
             let sttoken_denom = attributes.sttoken_denom.clone();
+            ensure_scope_permits_denom(scope.as_ref(), sender, &sttoken_denom)?;
+            ensure_denom_not_frozen(deps.storage, &sttoken_denom)?;
+
             let purchase_rate_value = Decimal::from_str(&new_metric.value)?;
+            ensure_within_bounds(
+                deps.storage,
+                BoundsTarget::Denom(sttoken_denom.clone()),
+                purchase_rate_value,
+            )?;
             let new_purchase_rate = PurchaseRate {
                 denom: sttoken_denom.clone(),
                 purchase_rate: purchase_rate_value,
                 update_time: new_metric.update_time,
+                received_time: new_metric.received_time,
+                received_height: new_metric.received_height,
             };
 
-            let mut purchase_rate_history =
-                match PURCHASE_RATES.may_load(deps.storage, &sttoken_denom)? {
-                    Some(history) => history,
-                    None => History::<PurchaseRate>::default(),
-                };
-            purchase_rate_history.add(new_purchase_rate);
-            PURCHASE_RATES.save(deps.storage, &sttoken_denom, &purchase_rate_history)?;
+            prepared_rate = Some(prepare_rate(
+                deps.storage,
+                &PURCHASE_RATES,
+                &new_metric,
+                new_purchase_rate,
+            )?);
         }
         MetricType::Other(_) => {}
     }
 
-    Ok(Response::new()
-        .add_attribute("action", "post_metric")
+    // If the key has a moving average, include the value in the key's average and, if the
+    // metric's rate was stored, in the denom's rate average
+    // Metrics held by the circuit breaker are included once they're approved
+    let held = matches!(prepared_rate, Some(PreparedRate::Trip(_)));
+    let mut ema = None;
+    let mut rate_ema = None;
+    if let Some(half_life) = EMA_HALF_LIVES.may_load(deps.storage, &key)? {
+        if metric_outcome != InsertOutcome::Dropped && !held {
+            ema = prepare_metric_ema(deps.storage, &new_metric, half_life)?;
+        }
+        if let Some(PreparedRate::Add(update, InsertOutcome::Inserted | InsertOutcome::Replaced)) =
+            prepared_rate.as_ref()
+        {
+            rate_ema = Some(prepare_rate_ema(deps.storage, update, half_life)?);
+        }
+    }
+
+    Ok(PreparedMetric {
+        metric: new_metric,
+        metric_history,
+        metric_outcome,
+        rate: prepared_rate,
+        slash_event,
+        ema,
+        rate_ema,
+    })
+}
+
+// Writes a prepared metric, and any derived rate, to their stores
+// Returns the metric's attributes and events, as well as the outcome of the post
+fn commit_metric(
+    storage: &mut dyn Storage,
+    prepared_metric: PreparedMetric,
+) -> Result<(Response, PostMetricResponse), ContractError> {
+    let data = prepared_metric.outcome();
+    let PreparedMetric {
+        metric: new_metric,
+        metric_history,
+        rate,
+        slash_event,
+        ema,
+        rate_ema,
+        ..
+    } = prepared_metric;
+
+    // If the rate tripped the circuit breaker, the metric is held with it until it's approved
+    if !matches!(rate, Some(PreparedRate::Trip(_))) {
+        METRICS.save(storage, &new_metric.key, &metric_history)?;
+    }
+    if let Some(ema) = ema {
+        EMAS.save(storage, &new_metric.key, &ema)?;
+    }
+
+    let mut response = Response::new()
         .add_attribute("metric_key", new_metric.key)
         .add_attribute("metric_value", new_metric.value)
         .add_attribute("metric_type", new_metric.metric_type.to_string())
@@ -149,5 +614,1029 @@ pub fn post_metric(
             new_metric
                 .attributes
                 .map_or("None".to_string(), |bin| bin.to_string()),
-        ))
+        )
+        .add_attribute("metric_outcome", data.metric_outcome.to_string());
+
+    if let Some(rate) = rate {
+        response = response.add_attribute("rate_outcome", rate.outcome().to_string());
+
+        match rate {
+            PreparedRate::Add(update, _) => {
+                if let Some(rate_ema) = rate_ema {
+                    rate_ema_store(&update).save(storage, update.denom(), &rate_ema)?;
+                }
+                add_rate_update(storage, update)?;
+            }
+            PreparedRate::Quarantine => {}
+            PreparedRate::Trip(pending_update) => {
+                PENDING_RATE_UPDATES.save(storage, &pending_update.denom, &*pending_update)?;
+
+                response = response.add_event(
+                    Event::new("circuit_breaker_tripped")
+                        .add_attribute("denom", pending_update.denom)
+                        .add_attribute("previous_rate", pending_update.previous_rate.to_string())
+                        .add_attribute("new_rate", pending_update.update.rate().to_string())
+                        .add_attribute("max_deviation", pending_update.max_deviation.to_string()),
+                );
+            }
+        }
+    }
+
+    if let Some(slash_event) = slash_event {
+        record_slash_event(storage, slash_event)?;
+        response = response.add_attribute("slash_recorded", "true");
+    }
+
+    Ok((response, data))
+}
+
+// Adds a slash event to the denom's slash history
+fn record_slash_event(
+    storage: &mut dyn Storage,
+    slash_event: SlashEvent,
+) -> Result<(), ContractError> {
+    let key = (slash_event.denom.as_str(), slash_event.update_time);
+    SLASH_EVENTS.save(storage, key, &slash_event)?;
+    Ok(())
+}
+
+// Checks a new metric against the existing history and the insert policy for its key
+// A metric is always rejected if an existing metric has the same time but a greater block height
+fn ensure_insert_permitted(
+    storage: &dyn Storage,
+    history: &History<Metric>,
+    new_metric: &Metric,
+) -> Result<(), ContractError> {
+    let policy = INSERT_POLICIES
+        .may_load(storage, &new_metric.key)?
+        .unwrap_or_default();
+
+    if let (InsertOrdering::StrictlyIncreasing, Some(latest)) =
+        (&policy.ordering, history.get_latest())
+    {
+        ensure!(
+            (new_metric.update_time, new_metric.block_height)
+                > (latest.update_time, latest.block_height),
+            ContractError::MetricNotNewer {
+                key: new_metric.key.clone(),
+                update_time: new_metric.update_time,
+                block_height: new_metric.block_height,
+                latest_update_time: latest.update_time,
+                latest_block_height: latest.block_height,
+            }
+        );
+    }
+
+    if let Some(existing) = history.get(new_metric.update_time) {
+        ensure!(
+            new_metric.block_height >= existing.block_height,
+            ContractError::MetricSuperseded {
+                key: new_metric.key.clone(),
+                update_time: new_metric.update_time,
+                block_height: new_metric.block_height,
+                existing_block_height: existing.block_height,
+            }
+        );
+
+        let conflicting = new_metric.block_height == existing.block_height
+            && (new_metric.value != existing.value
+                || new_metric.metric_type != existing.metric_type
+                || new_metric.attributes != existing.attributes);
+        ensure!(
+            !(conflicting && policy.duplicates == DuplicatePolicy::RejectConflicting),
+            ContractError::ConflictingMetric {
+                key: new_metric.key.clone(),
+                update_time: new_metric.update_time,
+            }
+        );
+    }
+
+    Ok(())
+}
+
+// Determines how a new redemption or purchase rate would be added to its store, after
+// checking it against the denom's deviation limit
+// If the limit is exceeded, the rate is either rejected, quarantined, or held by the
+// circuit breaker (which also freezes the denom)
+fn prepare_rate<T>(
+    storage: &dyn Storage,
+    store: &Map<&str, History<T>>,
+    metric: &Metric,
+    new_rate: T,
+) -> Result<PreparedRate, ContractError>
+where
+    T: Rate + Into<RateUpdate> + Serialize + DeserializeOwned,
+{
+    let denom = new_rate.denom().to_string();
+    let mut rate_history = store.may_load(storage, &denom)?.unwrap_or_default();
+
+    // Compare the new rate to the latest rate, if a deviation limit has been configured
+    let deviation_limit = RATE_DEVIATION_LIMITS.may_load(storage, &denom)?;
+    if let (Some(limit), Some(latest)) = (deviation_limit, rate_history.get_latest()) {
+        let elapsed_seconds = new_rate.time().saturating_sub(latest.time());
+        let max_deviation = limit.allowed_deviation(elapsed_seconds);
+        let deviation = relative_deviation(latest.rate(), new_rate.rate());
+
+        if deviation > max_deviation {
+            return match limit.action {
+                DeviationAction::Reject => Err(ContractError::RateDeviationExceeded {
+                    denom,
+                    previous: latest.rate(),
+                    new: new_rate.rate(),
+                    max_deviation,
+                }),
+                DeviationAction::Quarantine => Ok(PreparedRate::Quarantine),
+                DeviationAction::CircuitBreaker => {
+                    Ok(PreparedRate::Trip(Box::new(PendingRateUpdate {
+                        denom,
+                        metric: metric.clone(),
+                        update: new_rate.into(),
+                        previous_rate: latest.rate(),
+                        max_deviation,
+                        slash_event: None,
+                    })))
+                }
+            };
+        }
+    }
+
+    let outcome = rate_history.add(new_rate.clone());
+    Ok(PreparedRate::Add(new_rate.into(), outcome))
+}
+
+// Returns a key's average after including a new metric, if the metric's value is numeric
+// If the metric replaced the latest value in the average, the new value is re-applied to the
+// average from before the replaced value was included
+fn prepare_metric_ema(
+    storage: &dyn Storage,
+    metric: &Metric,
+    half_life: u64,
+) -> Result<Option<Ema>, ContractError> {
+    let Ok(value) = Decimal::from_str(&metric.value) else {
+        return Ok(None);
+    };
+    let previous = EMAS.may_load(storage, &metric.key)?;
+    Ok(Some(update_ema(
+        previous,
+        value,
+        metric.update_time,
+        half_life,
+    )))
+}
+
+// Returns a denom's rate average after including a new rate
+// If the rate replaced the latest rate in the average, the new rate is re-applied to the
+// average from before the replaced rate was included
+fn prepare_rate_ema(
+    storage: &dyn Storage,
+    update: &RateUpdate,
+    half_life: u64,
+) -> Result<Ema, ContractError> {
+    let previous = rate_ema_store(update).may_load(storage, update.denom())?;
+    Ok(update_ema(
+        previous,
+        update.rate(),
+        update.update_time(),
+        half_life,
+    ))
+}
+
+// Returns the store of moving averages for the type of rate in the update
+fn rate_ema_store(update: &RateUpdate) -> Map<'static, &'static str, Ema> {
+    match update {
+        RateUpdate::RedemptionRate(_) => REDEMPTION_RATE_EMAS,
+        RateUpdate::PurchaseRate(_) => PURCHASE_RATE_EMAS,
+    }
+}
+
+// Adds a rate update to the redemption or purchase rate store, returning the outcome
+fn add_rate_update(
+    storage: &mut dyn Storage,
+    update: RateUpdate,
+) -> Result<InsertOutcome, ContractError> {
+    let outcome = match update {
+        RateUpdate::RedemptionRate(redemption_rate) => {
+            let denom = redemption_rate.denom.clone();
+            let mut history = REDEMPTION_RATES
+                .may_load(storage, &denom)?
+                .unwrap_or_default();
+            let outcome = history.add(redemption_rate);
+            REDEMPTION_RATES.save(storage, &denom, &history)?;
+            outcome
+        }
+        RateUpdate::PurchaseRate(purchase_rate) => {
+            let denom = purchase_rate.denom.clone();
+            let mut history = PURCHASE_RATES
+                .may_load(storage, &denom)?
+                .unwrap_or_default();
+            let outcome = history.add(purchase_rate);
+            PURCHASE_RATES.save(storage, &denom, &history)?;
+            outcome
+        }
+    };
+    Ok(outcome)
+}
+
+/// Updates the transfer channel ID stored in the config
+/// Only the admin can update the config
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    transfer_channel_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    if let Some(channel_id) = transfer_channel_id.as_ref() {
+        validate_channel_id(channel_id)?;
+    }
+
+    let change = ConfigChange::UpdateConfig {
+        transfer_channel_id,
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Proposes a new admin address, which is stored as pending until the new address accepts
+/// Proposing a new address replaces any existing proposal
+pub fn propose_admin(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    admin_address: String,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::ProposeAdmin {
+        admin_address: deps.api.addr_validate(&admin_address)?,
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Completes the admin handover - must be called by the pending admin
+pub fn accept_admin(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    let Some(pending_admin_address) = config.pending_admin_address.take() else {
+        return Err(ContractError::NoPendingAdmin {});
+    };
+    ensure!(
+        info.sender == pending_admin_address,
+        ContractError::Unauthorized {}
+    );
+
+    let previous_admin_address = config.admin_address;
+    config.admin_address = pending_admin_address;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "accept_admin")
+        .add_attribute("previous_admin_address", previous_admin_address)
+        .add_attribute("admin_address", config.admin_address))
+}
+
+/// Cancels an in-progress admin handover
+pub fn cancel_admin_proposal(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut config = ensure_admin(deps.storage, &info.sender)?;
+
+    let Some(pending_admin_address) = config.pending_admin_address.take() else {
+        return Err(ContractError::NoPendingAdmin {});
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_admin_proposal")
+        .add_attribute("pending_admin_address", pending_admin_address))
+}
+
+/// Grants a role to an address
+/// Posters are added to the set of posters, while the guardian replaces any existing guardian
+pub fn grant_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::GrantRole {
+        role,
+        address: deps.api.addr_validate(&address)?,
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Revokes a role from an address
+pub fn revoke_role(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    role: Role,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::RevokeRole {
+        role,
+        address: deps.api.addr_validate(&address)?,
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Restricts the keys and metric types that a poster can post
+pub fn set_poster_scope(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    key_prefixes: Vec<String>,
+    metric_types: Vec<MetricType>,
+    denoms: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetPosterScope {
+        address: deps.api.addr_validate(&address)?,
+        scope: PosterScope {
+            key_prefixes,
+            metric_types,
+            denoms,
+        },
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Removes the scope from a poster so that they can post any metric
+pub fn remove_poster_scope(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::RemovePosterScope {
+        address: deps.api.addr_validate(&address)?,
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets the delay applied to config and role changes
+/// If a timelock is already active, this change is itself subject to the existing delay
+pub fn set_timelock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    timelock_seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    validate_timelock(timelock_seconds)?;
+
+    let change = ConfigChange::SetTimelock { timelock_seconds };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets or removes the deviation limit applied to a denom's redemption and purchase rates
+pub fn set_rate_deviation_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    limit: Option<RateDeviationLimit>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetRateDeviationLimit { denom, limit };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Enables or disables the monotonic redemption rate policy for a denom
+pub fn set_monotonic_redemption_rate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetMonotonicRedemptionRate { denom, enabled };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets or removes the hard bounds applied to a metric key or denom
+pub fn set_metric_bounds(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    target: BoundsTarget,
+    bounds: Option<MetricBounds>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    if let Some(bounds) = bounds.as_ref() {
+        if let (Some(min), Some(max)) = (bounds.min, bounds.max) {
+            ensure!(
+                min <= max,
+                ContractError::InvalidMetricBounds {
+                    bounds: bounds.clone()
+                }
+            );
+        }
+    }
+
+    let change = ConfigChange::SetMetricBounds { target, bounds };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets or removes the default max age of a denom's rates
+pub fn set_rate_max_age(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    max_age: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetRateMaxAge { denom, max_age };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets or removes the expected heartbeat interval of a metric key
+pub fn set_heartbeat(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    key: String,
+    interval: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetHeartbeat { key, interval };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets the limits on how far ahead of, or behind, the current block time a posted metric can be
+pub fn set_metric_time_limits(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_clock_skew: Option<u64>,
+    max_metric_age: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetMetricTimeLimits {
+        max_clock_skew,
+        max_metric_age,
+    };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets or removes the insert policy of a metric key
+pub fn set_insert_policy(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    key: String,
+    policy: Option<InsertPolicy>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    let change = ConfigChange::SetInsertPolicy { key, policy };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets the max number of metrics that can be posted in a single PostMetrics batch
+pub fn set_max_batch_size(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    max_batch_size: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    ensure!(
+        max_batch_size != Some(0),
+        ContractError::InvalidMaxBatchSize {}
+    );
+
+    let change = ConfigChange::SetMaxBatchSize { max_batch_size };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Sets or removes the half-life of a metric key's moving average
+pub fn set_ema_half_life(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    key: String,
+    half_life: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = ensure_admin(deps.storage, &info.sender)?;
+
+    ensure!(half_life != Some(0), ContractError::InvalidEmaHalfLife {});
+
+    let change = ConfigChange::SetEmaHalfLife { key, half_life };
+    submit_config_change(deps, env, &config, change)
+}
+
+/// Applies a queued config change once its timelock has expired
+pub fn execute_pending_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    ensure_admin(deps.storage, &info.sender)?;
+
+    let pending_change = PENDING_CHANGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::PendingChangeNotFound { id })?;
+    ensure!(
+        env.block.time.seconds() >= pending_change.execute_after,
+        ContractError::TimelockNotExpired {
+            id,
+            execute_after: pending_change.execute_after,
+        }
+    );
+
+    PENDING_CHANGES.remove(deps.storage, id);
+    let response = apply_config_change(deps, pending_change.change)?;
+
+    Ok(response.add_attribute("change_id", id.to_string()))
+}
+
+/// Cancels a queued config change before it is applied
+/// The guardian can cancel changes during the delay in case the admin is compromised
+pub fn cancel_pending_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    ensure_admin_or_guardian(deps.storage, &info.sender)?;
+
+    ensure!(
+        PENDING_CHANGES.has(deps.storage, id),
+        ContractError::PendingChangeNotFound { id }
+    );
+    PENDING_CHANGES.remove(deps.storage, id);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_pending_change")
+        .add_attribute("change_id", id.to_string()))
+}
+
+/// Approves a rate update that tripped the circuit breaker, adding it to the
+/// redemption/purchase rate store (along with the held metric) and releasing the denom
+/// A manual freeze on the denom is left in place
+/// Updates can't be approved while the contract is paused, or while the metric's key is frozen,
+/// since that would write a new rate
+pub fn approve_pending_update(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ensure_admin_or_guardian(deps.storage, &info.sender)?;
+    ensure_not_paused(deps.storage)?;
+
+    let pending_update = PENDING_RATE_UPDATES.may_load(deps.storage, &denom)?.ok_or(
+        ContractError::PendingUpdateNotFound {
+            denom: denom.clone(),
+        },
+    )?;
+    let PendingRateUpdate {
+        metric,
+        update,
+        slash_event,
+        ..
+    } = pending_update;
+    let new_rate = update.rate();
+
+    // The held metric can't be written if its key was frozen while the update was under review
+    ensure!(
+        !FROZEN_KEYS.has(deps.storage, &metric.key),
+        ContractError::KeyFrozen { key: metric.key }
+    );
+
+    // Add the held metric to the metric store, and its rate to the rate store
+    let mut metric_history = METRICS
+        .may_load(deps.storage, &metric.key)?
+        .unwrap_or_default();
+    let metric_outcome = metric_history.add(metric.clone());
+    METRICS.save(deps.storage, &metric.key, &metric_history)?;
+    let outcome = add_rate_update(deps.storage, update.clone())?;
+    let stored = matches!(outcome, InsertOutcome::Inserted | InsertOutcome::Replaced);
+
+    // If the key has a moving average, include the metric in the key's average and the
+    // approved rate in the denom's rate average
+    if let Some(half_life) = EMA_HALF_LIVES.may_load(deps.storage, &metric.key)? {
+        if metric_outcome != InsertOutcome::Dropped {
+            if let Some(ema) = prepare_metric_ema(deps.storage, &metric, half_life)? {
+                EMAS.save(deps.storage, &metric.key, &ema)?;
+            }
+        }
+        if stored {
+            let rate_ema = prepare_rate_ema(deps.storage, &update, half_life)?;
+            rate_ema_store(&update).save(deps.storage, update.denom(), &rate_ema)?;
+        }
+    }
+    PENDING_RATE_UPDATES.remove(deps.storage, &denom);
+
+    let mut response = Response::new()
+        .add_attribute("action", "approve_pending_update")
+        .add_attribute("denom", denom)
+        .add_attribute("metric_key", metric.key)
+        .add_attribute("rate", new_rate.to_string());
+
+    // If the update was flagged as a slash, record the slash event that was held with it
+    if let (Some(slash_event), true) = (slash_event, stored) {
+        record_slash_event(deps.storage, slash_event)?;
+        response = response.add_attribute("slash_recorded", "true");
+    }
+
+    Ok(response)
+}
+
+/// Rejects a rate update that tripped the circuit breaker, discarding it (along with the
+/// held metric) and releasing the denom
+pub fn reject_pending_update(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ensure_admin_or_guardian(deps.storage, &info.sender)?;
+
+    let pending_update = PENDING_RATE_UPDATES.may_load(deps.storage, &denom)?.ok_or(
+        ContractError::PendingUpdateNotFound {
+            denom: denom.clone(),
+        },
+    )?;
+    PENDING_RATE_UPDATES.remove(deps.storage, &denom);
+
+    Ok(Response::new()
+        .add_attribute("action", "reject_pending_update")
+        .add_attribute("denom", denom)
+        .add_attribute("rate", pending_update.update.rate().to_string()))
+}
+
+/// Pauses the contract, blocking all metric posts and rate queries
+pub fn pause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_admin_or_guardian(deps.storage, &info.sender)?;
+    PAUSED.save(deps.storage, &true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("sender", info.sender))
+}
+
+/// Unpauses the contract
+pub fn unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    ensure_admin(deps.storage, &info.sender)?;
+    PAUSED.save(deps.storage, &false)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("sender", info.sender))
+}
+
+/// Freezes a metric key so that it can no longer be posted to
+pub fn freeze_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    key: String,
+) -> Result<Response, ContractError> {
+    ensure_admin_or_guardian(deps.storage, &info.sender)?;
+    FROZEN_KEYS.save(deps.storage, &key, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze_key")
+        .add_attribute("metric_key", key))
+}
+
+/// Unfreezes a metric key
+pub fn unfreeze_key(
+    deps: DepsMut,
+    info: MessageInfo,
+    key: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(deps.storage, &info.sender)?;
+    FROZEN_KEYS.remove(deps.storage, &key);
+
+    Ok(Response::new()
+        .add_attribute("action", "unfreeze_key")
+        .add_attribute("metric_key", key))
+}
+
+/// Freezes a denom so that its redemption and purchase rates can no longer be posted or queried
+pub fn freeze_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ensure_admin_or_guardian(deps.storage, &info.sender)?;
+    FROZEN_DENOMS.save(deps.storage, &denom, &Empty {})?;
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze_denom")
+        .add_attribute("denom", denom))
+}
+
+/// Unfreezes a denom
+pub fn unfreeze_denom(
+    deps: DepsMut,
+    info: MessageInfo,
+    denom: String,
+) -> Result<Response, ContractError> {
+    ensure_admin(deps.storage, &info.sender)?;
+    FROZEN_DENOMS.remove(deps.storage, &denom);
+
+    Ok(Response::new()
+        .add_attribute("action", "unfreeze_denom")
+        .add_attribute("denom", denom))
+}
+
+// Applies a config change immediately if there is no timelock, otherwise
+// queues it to be executed once the timelock expires
+fn submit_config_change(
+    deps: DepsMut,
+    env: Env,
+    config: &Config,
+    change: ConfigChange,
+) -> Result<Response, ContractError> {
+    let Some(timelock_seconds) = config.timelock_seconds else {
+        return apply_config_change(deps, change);
+    };
+
+    let id = PENDING_CHANGE_ID
+        .may_load(deps.storage)?
+        .unwrap_or_default()
+        + 1;
+    let pending_change = PendingChange {
+        id,
+        execute_after: env.block.time.seconds() + timelock_seconds,
+        change,
+    };
+    PENDING_CHANGE_ID.save(deps.storage, &id)?;
+    PENDING_CHANGES.save(deps.storage, id, &pending_change)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "queue_config_change")
+        .add_attribute("change_id", id.to_string())
+        .add_attribute("change", pending_change.change.to_string())
+        .add_attribute("execute_after", pending_change.execute_after.to_string()))
+}
+
+/// Applies a config or role change to the store
+pub fn apply_config_change(deps: DepsMut, change: ConfigChange) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    let response = match change {
+        ConfigChange::UpdateConfig {
+            transfer_channel_id,
+        } => {
+            config.transfer_channel_id = transfer_channel_id;
+            CONFIG.save(deps.storage, &config)?;
+
+            Response::new()
+                .add_attribute("action", "update_config")
+                .add_attribute(
+                    "transfer_channel_id",
+                    config
+                        .transfer_channel_id
+                        .unwrap_or_else(|| "None".to_string()),
+                )
+        }
+        ConfigChange::ProposeAdmin { admin_address } => {
+            config.pending_admin_address = Some(admin_address.clone());
+            CONFIG.save(deps.storage, &config)?;
+
+            Response::new()
+                .add_attribute("action", "propose_admin")
+                .add_attribute("pending_admin_address", admin_address)
+        }
+        ConfigChange::GrantRole { role, address } => {
+            match role {
+                Role::Poster => POSTERS.save(deps.storage, &address, &Empty {})?,
+                Role::Guardian => {
+                    config.guardian_address = Some(address.clone());
+                    CONFIG.save(deps.storage, &config)?;
+                }
+            }
+
+            Response::new()
+                .add_attribute("action", "grant_role")
+                .add_attribute("role", role.to_string())
+                .add_attribute("address", address)
+        }
+        ConfigChange::RevokeRole { role, address } => {
+            let role_not_found = ContractError::RoleNotFound {
+                role: role.clone(),
+                address: address.to_string(),
+            };
+            match role {
+                Role::Poster => {
+                    ensure!(POSTERS.has(deps.storage, &address), role_not_found);
+                    POSTERS.remove(deps.storage, &address);
+                }
+                Role::Guardian => {
+                    ensure!(
+                        config.guardian_address.as_ref() == Some(&address),
+                        role_not_found
+                    );
+                    config.guardian_address = None;
+                    CONFIG.save(deps.storage, &config)?;
+                }
+            }
+
+            Response::new()
+                .add_attribute("action", "revoke_role")
+                .add_attribute("role", role.to_string())
+                .add_attribute("address", address)
+        }
+        ConfigChange::SetPosterScope { address, scope } => {
+            POSTER_SCOPES.save(deps.storage, &address, &scope)?;
+
+            Response::new()
+                .add_attribute("action", "set_poster_scope")
+                .add_attribute("address", address)
+                .add_attribute("key_prefixes", scope.key_prefixes.join(","))
+                .add_attribute(
+                    "metric_types",
+                    scope
+                        .metric_types
+                        .iter()
+                        .map(|metric_type| metric_type.to_string())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                )
+                .add_attribute("denoms", scope.denoms.join(","))
+        }
+        ConfigChange::RemovePosterScope { address } => {
+            POSTER_SCOPES.remove(deps.storage, &address);
+
+            Response::new()
+                .add_attribute("action", "remove_poster_scope")
+                .add_attribute("address", address)
+        }
+        ConfigChange::SetTimelock { timelock_seconds } => {
+            config.timelock_seconds = timelock_seconds;
+            CONFIG.save(deps.storage, &config)?;
+
+            Response::new()
+                .add_attribute("action", "set_timelock")
+                .add_attribute(
+                    "timelock_seconds",
+                    timelock_seconds.map_or("None".to_string(), |t| t.to_string()),
+                )
+        }
+        ConfigChange::SetRateDeviationLimit { denom, limit } => {
+            let max_relative_deviation = match limit {
+                Some(limit) => {
+                    let max_relative_deviation = limit.max_relative_deviation.to_string();
+                    RATE_DEVIATION_LIMITS.save(deps.storage, &denom, &limit)?;
+                    max_relative_deviation
+                }
+                None => {
+                    RATE_DEVIATION_LIMITS.remove(deps.storage, &denom);
+                    "None".to_string()
+                }
+            };
+
+            Response::new()
+                .add_attribute("action", "set_rate_deviation_limit")
+                .add_attribute("denom", denom)
+                .add_attribute("max_relative_deviation", max_relative_deviation)
+        }
+        ConfigChange::SetMonotonicRedemptionRate { denom, enabled } => {
+            if enabled {
+                MONOTONIC_DENOMS.save(deps.storage, &denom, &Empty {})?;
+            } else {
+                MONOTONIC_DENOMS.remove(deps.storage, &denom);
+            }
+
+            Response::new()
+                .add_attribute("action", "set_monotonic_redemption_rate")
+                .add_attribute("denom", denom)
+                .add_attribute("enabled", enabled.to_string())
+        }
+        ConfigChange::SetMetricBounds { target, bounds } => {
+            let store = match &target {
+                BoundsTarget::Key(_) => KEY_BOUNDS,
+                BoundsTarget::Denom(_) => DENOM_BOUNDS,
+            };
+            let (BoundsTarget::Key(id) | BoundsTarget::Denom(id)) = &target;
+            match bounds.as_ref() {
+                Some(bounds) => store.save(deps.storage, id, bounds)?,
+                None => store.remove(deps.storage, id),
+            }
+
+            Response::new()
+                .add_attribute("action", "set_metric_bounds")
+                .add_attribute("target", target.to_string())
+                .add_attribute(
+                    "bounds",
+                    bounds.map_or("None".to_string(), |bounds| bounds.to_string()),
+                )
+        }
+        ConfigChange::SetRateMaxAge { denom, max_age } => {
+            match max_age {
+                Some(max_age) => RATE_MAX_AGES.save(deps.storage, &denom, &max_age)?,
+                None => RATE_MAX_AGES.remove(deps.storage, &denom),
+            }
+
+            Response::new()
+                .add_attribute("action", "set_rate_max_age")
+                .add_attribute("denom", denom)
+                .add_attribute(
+                    "max_age",
+                    max_age.map_or("None".to_string(), |max_age| max_age.to_string()),
+                )
+        }
+        ConfigChange::SetHeartbeat { key, interval } => {
+            match interval {
+                Some(interval) => HEARTBEATS.save(deps.storage, &key, &interval)?,
+                None => HEARTBEATS.remove(deps.storage, &key),
+            }
+
+            Response::new()
+                .add_attribute("action", "set_heartbeat")
+                .add_attribute("metric_key", key)
+                .add_attribute(
+                    "interval",
+                    interval.map_or("None".to_string(), |interval| interval.to_string()),
+                )
+        }
+        ConfigChange::SetMetricTimeLimits {
+            max_clock_skew,
+            max_metric_age,
+        } => {
+            config.max_clock_skew = max_clock_skew;
+            config.max_metric_age = max_metric_age;
+            CONFIG.save(deps.storage, &config)?;
+
+            Response::new()
+                .add_attribute("action", "set_metric_time_limits")
+                .add_attribute(
+                    "max_clock_skew",
+                    max_clock_skew.map_or("None".to_string(), |t| t.to_string()),
+                )
+                .add_attribute(
+                    "max_metric_age",
+                    max_metric_age.map_or("None".to_string(), |t| t.to_string()),
+                )
+        }
+        ConfigChange::SetInsertPolicy { key, policy } => {
+            let response = Response::new()
+                .add_attribute("action", "set_insert_policy")
+                .add_attribute("metric_key", key.clone());
+
+            match policy {
+                Some(policy) => {
+                    INSERT_POLICIES.save(deps.storage, &key, &policy)?;
+                    response
+                        .add_attribute("ordering", policy.ordering.to_string())
+                        .add_attribute("duplicates", policy.duplicates.to_string())
+                }
+                None => {
+                    INSERT_POLICIES.remove(deps.storage, &key);
+                    response.add_attribute("policy", "None")
+                }
+            }
+        }
+        ConfigChange::SetMaxBatchSize { max_batch_size } => {
+            config.max_batch_size = max_batch_size;
+            CONFIG.save(deps.storage, &config)?;
+
+            Response::new()
+                .add_attribute("action", "set_max_batch_size")
+                .add_attribute(
+                    "max_batch_size",
+                    max_batch_size.map_or("None".to_string(), |size| size.to_string()),
+                )
+        }
+        ConfigChange::SetEmaHalfLife { key, half_life } => {
+            // Removing the half-life also removes the key's moving average, so that a stale
+            // average is not returned if the half-life is later restored
+            match half_life {
+                Some(half_life) => EMA_HALF_LIVES.save(deps.storage, &key, &half_life)?,
+                None => {
+                    EMA_HALF_LIVES.remove(deps.storage, &key);
+                    EMAS.remove(deps.storage, &key);
+                }
+            }
+
+            Response::new()
+                .add_attribute("action", "set_ema_half_life")
+                .add_attribute("metric_key", key)
+                .add_attribute(
+                    "half_life",
+                    half_life.map_or("None".to_string(), |half_life| half_life.to_string()),
+                )
+        }
+    };
+
+    Ok(response)
 }
@@ -1,8 +1,15 @@
+use crate::state::{
+    BoundsTarget, Ema, History, PosterScope, Rate, DENOM_BOUNDS, FROZEN_DENOMS, KEY_BOUNDS, PAUSED,
+    PENDING_RATE_UPDATES,
+};
 use crate::ContractError;
+use cosmwasm_std::{ensure, Addr, Decimal, Storage};
 use sha2::{Digest, Sha256};
 
 const CHANNEL_ID_PERFIX: &str = "channel";
 const TRANSFER_PORT_ID: &str = "transfer";
+const EMA_DECAY_FRACTION_BITS: u32 = 20;
+const MAX_TIMELOCK_SECONDS: u64 = 30 * 24 * 60 * 60;
 
 /// follows cosmos SDK validation logic where denoms can be 3 - 128 characters long
 /// and starts with a letter, followed but either a letter, number, or separator ( ‘/' , ‘:' , ‘.’ , ‘_’ , or '-')
@@ -35,6 +42,21 @@ pub fn validate_native_denom(denom: &str) -> Result<(), ContractError> {
     Ok(())
 }
 
+// Validates that the timelock does not exceed the max delay of 30 days
+// Without a max, a large timelock would overflow the execution time of every queued change
+pub fn validate_timelock(timelock_seconds: Option<u64>) -> Result<(), ContractError> {
+    if let Some(timelock_seconds) = timelock_seconds {
+        ensure!(
+            timelock_seconds <= MAX_TIMELOCK_SECONDS,
+            ContractError::TimelockTooLong {
+                timelock_seconds,
+                max_timelock_seconds: MAX_TIMELOCK_SECONDS,
+            }
+        );
+    }
+    Ok(())
+}
+
 // Validates that the channel ID is of the form `channel-N`
 pub fn validate_channel_id(channel_id: &str) -> Result<(), ContractError> {
     let Some((prefix, id)) = channel_id.split_once('-') else {
@@ -79,10 +101,206 @@ pub fn denom_trace_to_hash(base_denom: &str, channel_id: &str) -> Result<String,
     Ok(ibc_hash)
 }
 
+// Confirms the contract has not been paused
+pub fn ensure_not_paused(storage: &dyn Storage) -> Result<(), ContractError> {
+    ensure!(
+        !PAUSED.may_load(storage)?.unwrap_or_default(),
+        ContractError::Paused {}
+    );
+    Ok(())
+}
+
+// Confirms a denom's rates can be written to or read from
+// Fails if the contract is paused, the denom is frozen, or the denom has a rate update
+// held by the circuit breaker
+pub fn ensure_denom_not_frozen(storage: &dyn Storage, denom: &str) -> Result<(), ContractError> {
+    ensure_not_paused(storage)?;
+    ensure!(
+        !FROZEN_DENOMS.has(storage, denom),
+        ContractError::DenomFrozen {
+            denom: denom.to_string()
+        }
+    );
+    ensure!(
+        !PENDING_RATE_UPDATES.has(storage, denom),
+        ContractError::DenomHeld {
+            denom: denom.to_string()
+        }
+    );
+    Ok(())
+}
+
+// Confirms a poster's scope, if any, permits posting rates for the denom
+pub fn ensure_scope_permits_denom(
+    scope: Option<&PosterScope>,
+    poster: &Addr,
+    denom: &str,
+) -> Result<(), ContractError> {
+    ensure!(
+        !matches!(scope, Some(scope) if !scope.permits_denom(denom)),
+        ContractError::PosterDenomViolation {
+            poster: poster.to_string(),
+            denom: denom.to_string(),
+        }
+    );
+    Ok(())
+}
+
+// Confirms a value falls within the bounds configured for the key or denom, if any
+pub fn ensure_within_bounds(
+    storage: &dyn Storage,
+    target: BoundsTarget,
+    value: Decimal,
+) -> Result<(), ContractError> {
+    let bounds = match &target {
+        BoundsTarget::Key(key) => KEY_BOUNDS.may_load(storage, key)?,
+        BoundsTarget::Denom(denom) => DENOM_BOUNDS.may_load(storage, denom)?,
+    };
+    if let Some(bounds) = bounds {
+        ensure!(
+            bounds.contains(value),
+            ContractError::MetricOutOfBounds {
+                target,
+                value,
+                bounds,
+            }
+        );
+    }
+    Ok(())
+}
+
+// Returns the relative change between two values, i.e. |new - previous| / previous
+// If the previous value is zero, any change is treated as the maximum deviation
+pub fn relative_deviation(previous: Decimal, new: Decimal) -> Decimal {
+    let difference = previous.abs_diff(new);
+    if difference.is_zero() {
+        return Decimal::zero();
+    }
+    difference.checked_div(previous).unwrap_or(Decimal::MAX)
+}
+
+// Returns the time-weighted average of a rate history over the window from start to end
+// Each rate is treated as the current rate until the time of the next rate
+// Returns None if the window is empty, or if the history has no rate at or before the start
+pub fn time_weighted_average<T: Rate>(
+    history: &History<T>,
+    start: u64,
+    end: u64,
+) -> Option<Decimal> {
+    let window = end.checked_sub(start).filter(|window| *window > 0)?;
+    if history.iter().next()?.time() > start {
+        return None;
+    }
+
+    let mut average = Decimal::zero();
+    let mut rates = history.iter().peekable();
+    while let Some(rate) = rates.next() {
+        let next_time = rates.peek().map_or(end, |next| next.time());
+        let period_start = rate.time().max(start);
+        let period_end = next_time.min(end);
+        if period_end > period_start {
+            let weight = Decimal::from_ratio(period_end - period_start, window);
+            average += rate.rate() * weight;
+        }
+    }
+    Some(average)
+}
+
+// Linearly interpolates between two (time, value) points at the given time, which is
+// expected to fall between the two points
+pub fn linear_interpolation(before: (u64, Decimal), after: (u64, Decimal), time: u64) -> Decimal {
+    let (before_time, before_value) = before;
+    let (after_time, after_value) = after;
+    if after_time <= before_time {
+        return before_value;
+    }
+    let elapsed = time.clamp(before_time, after_time) - before_time;
+    let weight = Decimal::from_ratio(elapsed, after_time - before_time);
+    before_value * (Decimal::one() - weight) + after_value * weight
+}
+
+// Returns the factor by which a moving average decays over the elapsed time,
+// i.e. 0.5 ^ (elapsed_seconds / half_life)
+// The fractional part of the exponent is applied one binary digit at a time, where
+// each digit corresponds to a repeated square root of 0.5
+pub fn half_life_decay(elapsed_seconds: u64, half_life: u64) -> Decimal {
+    if half_life == 0 || elapsed_seconds / half_life >= 64 {
+        return Decimal::zero();
+    }
+    let half = Decimal::percent(50);
+    let mut decay = half.pow((elapsed_seconds / half_life) as u32);
+
+    let half_life = half_life as u128;
+    let mut remainder = (elapsed_seconds as u128) % half_life;
+    let mut factor = half;
+    for _ in 0..EMA_DECAY_FRACTION_BITS {
+        factor = factor.sqrt();
+        remainder *= 2;
+        if remainder >= half_life {
+            remainder -= half_life;
+            decay *= factor;
+        }
+    }
+    decay
+}
+
+// Returns the moving average after including a new value
+// A value with the same time as the newest value replaces it, by re-applying the new value
+// to the average from before the newest value was included
+// Values that are older than the newest value in the average are ignored
+pub fn update_ema(ema: Option<Ema>, value: Decimal, update_time: u64, half_life: u64) -> Ema {
+    match ema {
+        Some(ema) if update_time < ema.update_time => ema,
+        Some(ema) if update_time == ema.update_time => {
+            let previous = ema.previous.map(|previous| *previous);
+            include_in_ema(previous, value, update_time, half_life)
+        }
+        ema => include_in_ema(ema, value, update_time, half_life),
+    }
+}
+
+// Folds a value that's newer than the average into the average
+fn include_in_ema(ema: Option<Ema>, value: Decimal, update_time: u64, half_life: u64) -> Ema {
+    match ema {
+        Some(ema) => {
+            let decay = half_life_decay(update_time - ema.update_time, half_life);
+            Ema {
+                value: ema.value * decay + value * (Decimal::one() - decay),
+                update_time,
+                previous: Some(Box::new(Ema {
+                    previous: None,
+                    ..ema
+                })),
+            }
+        }
+        None => Ema {
+            value,
+            update_time,
+            previous: None,
+        },
+    }
+}
+
+// Builds a moving average from a list of (update_time, value) pairs sorted by time
+pub fn ema_from_values(
+    values: impl IntoIterator<Item = (u64, Decimal)>,
+    half_life: u64,
+) -> Option<Ema> {
+    values.into_iter().fold(None, |ema, (update_time, value)| {
+        Some(update_ema(ema, value, update_time, half_life))
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::helpers::{denom_trace_to_hash, validate_channel_id, validate_native_denom};
+    use crate::helpers::{
+        denom_trace_to_hash, ema_from_values, half_life_decay, relative_deviation,
+        time_weighted_average, update_ema, validate_channel_id, validate_native_denom,
+    };
+    use crate::state::{Ema, History, RedemptionRate};
     use crate::ContractError;
+    use cosmwasm_std::Decimal;
+    use std::str::FromStr;
 
     #[test]
     fn length_below_three() {
@@ -235,4 +453,105 @@ mod tests {
             }),
         );
     }
+
+    #[test]
+    fn test_relative_deviation() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        assert_eq!(relative_deviation(dec("1.0"), dec("1.0")), Decimal::zero());
+        assert_eq!(relative_deviation(dec("1.0"), dec("1.1")), dec("0.1"));
+        assert_eq!(relative_deviation(dec("2.0"), dec("1.5")), dec("0.25"));
+        assert_eq!(relative_deviation(dec("0"), dec("0")), Decimal::zero());
+        assert_eq!(relative_deviation(dec("0"), dec("1")), Decimal::MAX);
+    }
+
+    #[test]
+    fn test_time_weighted_average() {
+        let mut history = History::default();
+        for (value, time) in [("1.0", 100), ("2.0", 200), ("4.0", 300)] {
+            history.add(RedemptionRate {
+                denom: "denom".to_string(),
+                redemption_rate: Decimal::from_str(value).unwrap(),
+                update_time: time,
+                received_time: None,
+                received_height: None,
+            });
+        }
+        let twap = |start, end| time_weighted_average(&history, start, end);
+
+        // Each rate is weighted by the time until the next rate
+        assert_eq!(twap(100, 300), Some(Decimal::from_str("1.5").unwrap()));
+        assert_eq!(twap(150, 400), Some(Decimal::from_str("2.6").unwrap()));
+
+        // A window after the latest rate returns the latest rate
+        assert_eq!(twap(300, 400), Some(Decimal::from_str("4.0").unwrap()));
+
+        // A window that starts before the history, or is empty, has no average
+        assert_eq!(twap(50, 300), None);
+        assert_eq!(twap(300, 300), None);
+        assert_eq!(
+            time_weighted_average(&History::<RedemptionRate>::default(), 0, 1),
+            None
+        );
+    }
+
+    #[test]
+    fn test_half_life_decay() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        // Whole half-lives halve the value each time
+        assert_eq!(half_life_decay(0, 100), Decimal::one());
+        assert_eq!(half_life_decay(100, 100), dec("0.5"));
+        assert_eq!(half_life_decay(300, 100), dec("0.125"));
+
+        // Partial half-lives are approximated to within a small error
+        let decay = half_life_decay(50, 100);
+        assert!(decay.abs_diff(dec("0.707106781186547524")) < dec("0.00001"));
+        let decay = half_life_decay(175, 100);
+        assert!(decay.abs_diff(dec("0.297301778750680868")) < dec("0.00001"));
+
+        // After enough half-lives, the decay rounds to zero
+        assert_eq!(half_life_decay(6400, 100), Decimal::zero());
+        assert_eq!(half_life_decay(100, 0), Decimal::zero());
+    }
+
+    #[test]
+    fn test_update_ema() {
+        let dec = |value: &str| Decimal::from_str(value).unwrap();
+
+        // The first value seeds the average
+        let first = update_ema(None, dec("1.0"), 100, 100);
+        assert_eq!(
+            first,
+            Ema {
+                value: dec("1.0"),
+                update_time: 100,
+                previous: None,
+            }
+        );
+
+        // After one half-life, the new value has half the weight
+        let ema = update_ema(Some(first.clone()), dec("2.0"), 200, 100);
+        assert_eq!(
+            ema,
+            Ema {
+                value: dec("1.5"),
+                update_time: 200,
+                previous: Some(Box::new(first)),
+            }
+        );
+
+        // Values that are older than the average are ignored
+        assert_eq!(update_ema(Some(ema.clone()), dec("9.0"), 150, 100), ema);
+
+        // A value with the same time as the newest value replaces it
+        let replaced = update_ema(Some(ema.clone()), dec("3.0"), 200, 100);
+        assert_eq!(replaced.value, dec("2.0"));
+        assert_eq!(update_ema(Some(replaced), dec("2.0"), 200, 100), ema);
+
+        // Building from a list of values gives the same result
+        let values = vec![(100, dec("1.0")), (200, dec("2.0"))];
+        assert_eq!(ema_from_values(values, 100), Some(ema));
+        assert_eq!(ema_from_values(vec![], 100), None);
+    }
 }
@@ -6,5 +6,6 @@ pub mod state;
 
 mod execute;
 mod query;
+mod sudo;
 
 pub use crate::error::ContractError;
@@ -1,6 +1,11 @@
-use crate::state::{Metric, MetricType, RedemptionRate, PurchaseRate};
+use crate::state::{
+    BoundsTarget, InsertOutcome, InsertPolicy, Metric, MetricBounds, MetricType, PendingChange,
+    PendingRateUpdate, PosterScope, PurchaseRate, RateDeviationLimit, RedemptionRate, Role,
+    SlashEvent,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Decimal};
+use cosmwasm_std::{Addr, Binary, Decimal, Order};
+use std::fmt;
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -33,7 +38,235 @@ pub enum ExecuteMsg {
         block_height: u64,
         /// Additional metric-specific attributes
         attributes: Option<Binary>,
+        /// If set, the metric is only stored if the update time of the latest metric
+        /// for the key matches this value
+        expected_latest_update_time: Option<u64>,
     },
+
+    /// Uploads and stores a batch of metrics
+    /// Each metric is handled the same as in PostMetric, and if any metric is rejected,
+    /// the whole batch fails
+    PostMetrics { metrics: Vec<MetricUpdate> },
+
+    /// Uploads and stores a new redemption rate
+    /// This is equivalent to posting a redemption rate metric with PostMetric, without
+    /// having to encode the attributes
+    PostRedemptionRate {
+        /// The stToken denom as it appears on the controller chain (e.g. `stuosmo`)
+        denom: String,
+        /// The redemption rate of the stToken
+        redemption_rate: Decimal,
+        /// Unix timestamp with which the rate was updated on the source chain
+        update_time: u64,
+        /// Block height with which the rate was updated on the source chain
+        block_height: u64,
+        /// Key under which the metric is stored
+        /// Defaults to `{denom}_redemption_rate` (e.g. `stuosmo_redemption_rate`)
+        key: Option<String>,
+        /// Whether the update is the result of a slash
+        #[serde(default)]
+        slash: bool,
+    },
+
+    /// Uploads and stores a new purchase rate
+    /// This is equivalent to posting a purchase rate metric with PostMetric, without
+    /// having to encode the attributes
+    PostPurchaseRate {
+        /// The stToken denom as it appears on the controller chain (e.g. `stuosmo`)
+        denom: String,
+        /// The purchase rate of the stToken
+        purchase_rate: Decimal,
+        /// Unix timestamp with which the rate was updated on the source chain
+        update_time: u64,
+        /// Block height with which the rate was updated on the source chain
+        block_height: u64,
+        /// Key under which the metric is stored
+        /// Defaults to `{denom}_purchase_rate` (e.g. `stuosmo_purchase_rate`)
+        key: Option<String>,
+    },
+
+    /// Updates the contract config (only callable by the admin)
+    UpdateConfig {
+        /// The new transfer channel ID from the Oracle chain to the Controller chain
+        /// Passing None clears the channel ID
+        transfer_channel_id: Option<String>,
+    },
+
+    /// Proposes a new admin address (only callable by the current admin)
+    /// The handover is not complete until the proposed address calls AcceptAdmin
+    ProposeAdmin { admin_address: String },
+
+    /// Accepts a pending admin proposal (only callable by the proposed admin)
+    AcceptAdmin {},
+
+    /// Cancels a pending admin proposal (only callable by the current admin)
+    CancelAdminProposal {},
+
+    /// Grants a role to an address (only callable by the admin)
+    /// Granting the guardian role replaces the existing guardian
+    GrantRole { role: Role, address: String },
+
+    /// Revokes a role from an address (only callable by the admin)
+    RevokeRole { role: Role, address: String },
+
+    /// Restricts the metrics that a poster can post (only callable by the admin)
+    /// Replaces any existing scope for the poster
+    SetPosterScope {
+        /// Address of the poster
+        address: String,
+        /// If non-empty, the metric key must start with one of these prefixes
+        key_prefixes: Vec<String>,
+        /// If non-empty, the metric type must be one of these types
+        metric_types: Vec<MetricType>,
+        /// If non-empty, the denom of a redemption or purchase rate must be one of these denoms
+        #[serde(default)]
+        denoms: Vec<String>,
+    },
+
+    /// Removes a poster's scope, allowing them to post any metric (only callable by the admin)
+    RemovePosterScope { address: String },
+
+    /// Sets the delay applied to config and role changes (only callable by the admin)
+    /// While a timelock is set, the above admin messages are queued rather than applied
+    /// Passing None disables the timelock
+    SetTimelock { timelock_seconds: Option<u64> },
+
+    /// Sets the maximum deviation allowed between consecutive redemption or purchase rates
+    /// for a denom (only callable by the admin)
+    /// Passing None removes the limit
+    SetRateDeviationLimit {
+        denom: String,
+        limit: Option<RateDeviationLimit>,
+    },
+
+    /// Enables or disables the monotonic redemption rate policy for a denom (only callable by the admin)
+    /// While enabled, the denom's redemption rate can only decrease if the update is flagged as a slash
+    SetMonotonicRedemptionRate { denom: String, enabled: bool },
+
+    /// Sets the hard bounds applied to the value of a metric key, or to the redemption and
+    /// purchase rates of a denom (only callable by the admin)
+    /// Passing None removes the bounds
+    SetMetricBounds {
+        target: BoundsTarget,
+        bounds: Option<MetricBounds>,
+    },
+
+    /// Sets the default max age (in seconds) of a denom's rates (only callable by the admin)
+    /// Rate queries that don't pass a max age fail if the latest rate is older than this
+    /// Passing None removes the default
+    SetRateMaxAge { denom: String, max_age: Option<u64> },
+
+    /// Sets the expected interval (in seconds) between updates of a metric key, which is
+    /// used to determine the key's status in the Health query (only callable by the admin)
+    /// Passing None stops monitoring the key
+    SetHeartbeat { key: String, interval: Option<u64> },
+
+    /// Sets the limits on how far a posted metric's update time can be from the current
+    /// block time (only callable by the admin)
+    SetMetricTimeLimits {
+        /// Maximum seconds that an update time can be ahead of the block time
+        /// Passing None allows any future update time
+        max_clock_skew: Option<u64>,
+        /// Maximum seconds that an update time can be behind the block time
+        /// Passing None allows any past update time
+        max_metric_age: Option<u64>,
+    },
+
+    /// Sets the policy applied when inserting a new metric into a key's history
+    /// (only callable by the admin)
+    /// Passing None restores the default policy (allow backfill, replace on equal)
+    SetInsertPolicy {
+        key: String,
+        policy: Option<InsertPolicy>,
+    },
+
+    /// Sets the maximum number of metrics in a PostMetrics batch (only callable by the admin)
+    /// Passing None restores the default limit
+    SetMaxBatchSize { max_batch_size: Option<u64> },
+
+    /// Sets the half-life (in seconds) of the moving average of a metric key, which is updated
+    /// with each new numeric value posted to the key (only callable by the admin)
+    /// Passing None removes the key's moving average
+    SetEmaHalfLife { key: String, half_life: Option<u64> },
+
+    /// Applies a queued config change once its timelock has expired (only callable by the admin)
+    ExecutePendingChange { id: u64 },
+
+    /// Cancels a queued config change (callable by the admin or guardian)
+    CancelPendingChange { id: u64 },
+
+    /// Approves a rate update held by the circuit breaker, storing the rate (and the metric
+    /// it was posted with) and releasing the denom (callable by the admin or guardian)
+    ApprovePendingUpdate { denom: String },
+
+    /// Rejects a rate update held by the circuit breaker, discarding the rate (and the metric
+    /// it was posted with) and releasing the denom (callable by the admin or guardian)
+    RejectPendingUpdate { denom: String },
+
+    /// Pauses all metric posts and rate queries (callable by the admin or guardian)
+    Pause {},
+
+    /// Unpauses the contract (only callable by the admin)
+    Unpause {},
+
+    /// Blocks posts to a metric key (callable by the admin or guardian)
+    FreezeKey { key: String },
+
+    /// Unfreezes a metric key (only callable by the admin)
+    UnfreezeKey { key: String },
+
+    /// Blocks posts to, and queries of, a denom's redemption and purchase rates
+    /// (callable by the admin or guardian)
+    FreezeDenom { denom: String },
+
+    /// Unfreezes a denom (only callable by the admin)
+    UnfreezeDenom { denom: String },
+}
+
+/// A single metric within a PostMetrics batch
+/// The fields match those of PostMetric
+#[cw_serde]
+pub struct MetricUpdate {
+    pub key: String,
+    pub value: String,
+    pub metric_type: MetricType,
+    pub update_time: u64,
+    pub block_height: u64,
+    pub attributes: Option<Binary>,
+    pub expected_latest_update_time: Option<u64>,
+}
+
+/// Messages that can only be executed by the chain (e.g. via governance)
+/// This provides a recovery path that does not depend on the admin
+#[cw_serde]
+pub enum SudoMsg {
+    /// Replaces the admin address directly, clearing any pending admin handover
+    UpdateAdmin { admin_address: String },
+
+    /// Pauses all metric posts and rate queries
+    Pause {},
+
+    /// Unpauses the contract
+    Unpause {},
+
+    /// Removes a metric from the store, along with the corresponding redemption/purchase rate
+    /// If the update time is not specified, the full history of the metric is removed
+    /// Slash events are never removed, so that they remain a complete audit trail
+    RemoveMetric {
+        key: String,
+        update_time: Option<u64>,
+    },
+
+    /// Updates the contract config
+    UpdateConfig {
+        /// The new transfer channel ID from the Oracle chain to the Controller chain
+        /// Passing None clears the channel ID
+        transfer_channel_id: Option<String>,
+    },
+
+    /// Sets or removes the timelock immediately, without queueing the change
+    /// This allows governance to recover if the admin sets a timelock that's too long
+    SetTimelock { timelock_seconds: Option<u64> },
 }
 
 #[cw_serde]
@@ -43,27 +276,144 @@ pub enum QueryMsg {
     #[returns(crate::state::Config)]
     Config {},
 
+    /// Returns the admin, guardian, and set of posters
+    #[returns(RolesResponse)]
+    Roles {},
+
+    /// Returns the scope of each poster that has restrictions
+    /// Paginated by poster address
+    #[returns(PosterScopesResponse)]
+    PosterScopes {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the config changes that are queued behind the timelock
+    /// Paginated by change ID
+    #[returns(PendingChangesResponse)]
+    PendingChanges {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the deviation limit configured for each denom
+    /// Paginated by denom
+    #[returns(RateDeviationLimitsResponse)]
+    RateDeviationLimits {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the denoms with the monotonic redemption rate policy enabled
+    /// Paginated by denom
+    #[returns(MonotonicDenomsResponse)]
+    MonotonicDenoms {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the hard bounds configured for each metric key and denom
+    #[returns(MetricBoundsResponse)]
+    MetricBounds {},
+
+    /// Returns the rate updates held by the circuit breaker, awaiting review
+    /// Paginated by denom
+    #[returns(PendingUpdatesResponse)]
+    PendingUpdates {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the default max age configured for each denom's rates
+    /// Paginated by denom
+    #[returns(RateMaxAgesResponse)]
+    RateMaxAges {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns the insert policy configured for each metric key
+    /// Paginated by key
+    #[returns(InsertPoliciesResponse)]
+    InsertPolicies {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+
+    /// Returns whether the contract is paused, as well as the frozen keys and denoms
+    #[returns(FreezeStatusResponse)]
+    FreezeStatus {},
+
+    /// Returns the time since the last update of each metric key, along with its status
+    /// relative to the key's heartbeat interval
+    #[returns(HealthResponse)]
+    Health {},
+
+    /// Runs the full validation of a PostMetric from the given sender, without storing
+    /// the metric, and returns the outcome that the post would have or the error that
+    /// it would fail with
+    #[returns(SimulatePostMetricResponse)]
+    SimulatePostMetric {
+        sender: String,
+        metric: MetricUpdate,
+    },
+
     /// Returns the latest metric, given the metric's key
     #[returns(Metric)]
     Metric { key: String },
 
-    /// Returns the full history of values for a given metric key, up to the capacity
-    /// Includes optional limit on the number of metrics returned
+    /// Returns the moving average of a metric key, along with its half-life
+    #[returns(EmaResponse)]
+    Ema { key: String },
+
+    /// Returns a page of the history of values for a given metric key, up to the capacity
     #[returns(Metrics)]
-    HistoricalMetrics { key: String, limit: Option<u64> },
+    HistoricalMetrics {
+        key: String,
+        /// Optional filter on the update time of the entries returned (inclusive)
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        /// Optional cursor, set to the update time of the last entry from the previous page
+        start_after: Option<u64>,
+        /// Optional order of the entries returned (defaults to newest first)
+        order: Option<HistoryOrder>,
+        /// Optional limit on the number of entries to return (capped at 50)
+        limit: Option<u64>,
+    },
+
+    /// Returns the metric in effect at the given time, which is the latest metric at or before it
+    /// If interpolate is true, the metric's value is linearly interpolated with the next metric
+    /// (this requires the values to be numeric)
+    #[returns(Metric)]
+    MetricAt {
+        key: String,
+        time: u64,
+        #[serde(default)]
+        interpolate: bool,
+    },
 
     /// Returns the latest metric for each key
     #[returns(Metrics)]
     AllLatestMetrics {},
 
+    /// Returns summary statistics of a metric key's numeric values, optionally restricted
+    /// to the values with an update time between since and until (inclusive)
+    #[returns(MetricStatsResponse)]
+    MetricStats {
+        key: String,
+        since: Option<u64>,
+        until: Option<u64>,
+    },
+
     /// Returns the redemption rate of an stToken
     #[returns(RedemptionRateResponse)]
     RedemptionRate {
         /// The denom should be the ibc hash of an stToken as it lives on the oracle chain
         /// (e.g. ibc/{hash(transfer/channel-326/stuatom)} on Osmosis)
         denom: String,
-        /// Params should always be None, but was included in this query
-        /// to align with other price oracles that take additional parameters such as TWAP
+        /// Optional params, encoded as RateQueryParams
+        /// (e.g. `{"max_age": 3600}` to reject a rate older than an hour, or
+        /// `{"twap_window": 86400}` to return the average rate over the last day)
         params: Option<Binary>,
     },
 
@@ -76,21 +426,40 @@ pub enum QueryMsg {
         /// Params should always be None, but was included in this query
         /// to align with other price oracles that take additional parameters such as TWAP
         params: Option<Binary>,
-        /// Optional limit on the number of entries to return
+        /// Optional filter on the update time of the entries returned (inclusive)
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        /// Optional cursor, set to the update time of the last entry from the previous page
+        start_after: Option<u64>,
+        /// Optional order of the entries returned (defaults to newest first)
+        order: Option<HistoryOrder>,
+        /// Optional limit on the number of entries to return (capped at 50)
         limit: Option<u64>,
     },
 
+    /// Returns the redemption rate of an stToken in effect at the given time, which is the
+    /// latest rate at or before it (the returned update time is that of this rate)
+    /// If interpolate is true, the rate is linearly interpolated with the next rate
+    #[returns(RedemptionRateResponse)]
+    RedemptionRateAt {
+        denom: String,
+        time: u64,
+        #[serde(default)]
+        interpolate: bool,
+    },
+
     /// Returns the purchase rate of an milkTia
     #[returns(PurchaseRateResponse)]
     PurchaseRate {
         /// The denom should be the ibc hash of an milkTia as it lives on the oracle chain
         denom: String,
-        /// Params should always be None, but was included in this query
-        /// to align with other price oracles that take additional parameters such as TWAP
+        /// Optional params, encoded as RateQueryParams
+        /// (e.g. `{"max_age": 3600}` to reject a rate older than an hour, or
+        /// `{"twap_window": 86400}` to return the average rate over the last day)
         params: Option<Binary>,
     },
 
-    /// Returns a list of redemption rates over time for an stToken
+    /// Returns a list of purchase rates over time for an stToken
     #[returns(PurchaseRates)]
     HistoricalPurchaseRates {
         /// The denom should be the ibc hash of an stToken as it lives on the oracle chain
@@ -99,9 +468,284 @@ pub enum QueryMsg {
         /// Params should always be None, but was included in this query
         /// to align with other price oracles that take additional parameters such as TWAP
         params: Option<Binary>,
-        /// Optional limit on the number of entries to return
+        /// Optional filter on the update time of the entries returned (inclusive)
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        /// Optional cursor, set to the update time of the last entry from the previous page
+        start_after: Option<u64>,
+        /// Optional order of the entries returned (defaults to newest first)
+        order: Option<HistoryOrder>,
+        /// Optional limit on the number of entries to return (capped at 50)
         limit: Option<u64>,
     },
+
+    /// Returns the purchase rate of a milkTia in effect at the given time, which is the
+    /// latest rate at or before it (the returned update time is that of this rate)
+    /// If interpolate is true, the rate is linearly interpolated with the next rate
+    #[returns(PurchaseRateResponse)]
+    PurchaseRateAt {
+        denom: String,
+        time: u64,
+        #[serde(default)]
+        interpolate: bool,
+    },
+
+    /// Returns the history of slash events (redemption rate decreases) for an stToken,
+    /// sorted by the time at which the slash occurred
+    #[returns(SlashEvents)]
+    SlashEvents {
+        denom: String,
+        /// Optional filter on the update time of the events returned (inclusive)
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        /// Optional cursor, set to the update time of the last event from the previous page
+        start_after: Option<u64>,
+        /// Optional order of the events returned (defaults to newest first)
+        order: Option<HistoryOrder>,
+        /// Optional limit on the number of events to return (capped at 50)
+        limit: Option<u64>,
+    },
+}
+
+/// Optional parameters for the RedemptionRate and PurchaseRate queries
+#[cw_serde]
+#[derive(Default)]
+pub struct RateQueryParams {
+    /// Maximum age (in seconds) of the rate, relative to the current block time
+    /// If not specified, the denom's default max age is used
+    pub max_age: Option<u64>,
+    /// If specified, the time-weighted average rate over this many seconds (ending at the
+    /// current block time) is returned instead of the latest rate
+    pub twap_window: Option<u64>,
+    /// If true, the moving average of the rate is returned instead of the latest rate
+    /// This cannot be combined with a TWAP window
+    #[serde(default)]
+    pub ema: bool,
+}
+
+/// The order in which historical queries return entries, by update time
+#[cw_serde]
+#[derive(Default)]
+pub enum HistoryOrder {
+    Ascending,
+    #[default]
+    Descending,
+}
+
+impl From<HistoryOrder> for Order {
+    fn from(order: HistoryOrder) -> Self {
+        match order {
+            HistoryOrder::Ascending => Order::Ascending,
+            HistoryOrder::Descending => Order::Descending,
+        }
+    }
+}
+
+/// The outcome of adding a metric to the metrics store
+///  - Inserted/Replaced/Dropped: the metric was added to the store with the given outcome
+///  - Held: the metric's rate tripped the circuit breaker, and the metric is held with the
+///    rate until it's approved
+#[cw_serde]
+pub enum MetricOutcome {
+    Inserted,
+    Replaced,
+    Dropped,
+    Held,
+}
+
+impl From<InsertOutcome> for MetricOutcome {
+    fn from(outcome: InsertOutcome) -> Self {
+        match outcome {
+            InsertOutcome::Inserted => MetricOutcome::Inserted,
+            InsertOutcome::Replaced => MetricOutcome::Replaced,
+            InsertOutcome::Dropped => MetricOutcome::Dropped,
+        }
+    }
+}
+
+impl fmt::Display for MetricOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MetricOutcome::Inserted => write!(f, "inserted"),
+            MetricOutcome::Replaced => write!(f, "replaced"),
+            MetricOutcome::Dropped => write!(f, "dropped"),
+            MetricOutcome::Held => write!(f, "held"),
+        }
+    }
+}
+
+/// The outcome of adding a redemption or purchase rate to its store
+///  - Inserted/Replaced/Dropped: the rate passed the safety checks and was added to the store
+///    with the given outcome
+///  - Quarantined: the rate exceeded the deviation limit and was not stored
+///  - CircuitBreakerTripped: the rate exceeded the deviation limit and is held for review
+#[cw_serde]
+pub enum RateOutcome {
+    Inserted,
+    Replaced,
+    Dropped,
+    Quarantined,
+    CircuitBreakerTripped,
+}
+
+impl From<InsertOutcome> for RateOutcome {
+    fn from(outcome: InsertOutcome) -> Self {
+        match outcome {
+            InsertOutcome::Inserted => RateOutcome::Inserted,
+            InsertOutcome::Replaced => RateOutcome::Replaced,
+            InsertOutcome::Dropped => RateOutcome::Dropped,
+        }
+    }
+}
+
+impl fmt::Display for RateOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RateOutcome::Inserted => write!(f, "inserted"),
+            RateOutcome::Replaced => write!(f, "replaced"),
+            RateOutcome::Dropped => write!(f, "dropped"),
+            RateOutcome::Quarantined => write!(f, "quarantined"),
+            RateOutcome::CircuitBreakerTripped => write!(f, "circuit_breaker_tripped"),
+        }
+    }
+}
+
+/// Response data set by PostMetric
+///  - metric_outcome is the outcome of adding the metric to the metrics store, or held if the
+///    metric is held by the circuit breaker
+///  - rate_outcome is the outcome of adding the derived rate to the redemption or
+///    purchase rate store (only set for redemption and purchase rate metrics)
+#[cw_serde]
+pub struct PostMetricResponse {
+    pub metric_outcome: MetricOutcome,
+    pub rate_outcome: Option<RateOutcome>,
+}
+
+/// The result of a simulated post
+///  - outcome is the response data that PostMetric would return, if the post succeeds
+///  - error is the error message that PostMetric would fail with, if the post fails
+#[cw_serde]
+pub struct SimulatePostMetricResponse {
+    pub outcome: Option<PostMetricResponse>,
+    pub error: Option<String>,
+}
+
+/// Response data set by PostMetrics, with one outcome per metric in the batch
+#[cw_serde]
+pub struct PostMetricsResponse {
+    pub outcomes: Vec<PostMetricResponse>,
+}
+
+#[cw_serde]
+pub struct RolesResponse {
+    pub admin_address: Addr,
+    pub pending_admin_address: Option<Addr>,
+    pub guardian_address: Option<Addr>,
+    pub posters: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct PosterScopeEntry {
+    pub address: Addr,
+    pub scope: PosterScope,
+}
+
+#[cw_serde]
+pub struct PosterScopesResponse {
+    pub scopes: Vec<PosterScopeEntry>,
+}
+
+#[cw_serde]
+pub struct PendingChangesResponse {
+    pub changes: Vec<PendingChange>,
+}
+
+#[cw_serde]
+pub struct RateDeviationLimitEntry {
+    pub denom: String,
+    pub limit: RateDeviationLimit,
+}
+
+#[cw_serde]
+pub struct RateDeviationLimitsResponse {
+    pub limits: Vec<RateDeviationLimitEntry>,
+}
+
+#[cw_serde]
+pub struct MonotonicDenomsResponse {
+    pub denoms: Vec<String>,
+}
+
+#[cw_serde]
+pub struct MetricBoundsEntry {
+    pub target: BoundsTarget,
+    pub bounds: MetricBounds,
+}
+
+#[cw_serde]
+pub struct MetricBoundsResponse {
+    pub bounds: Vec<MetricBoundsEntry>,
+}
+
+#[cw_serde]
+pub struct PendingUpdatesResponse {
+    pub updates: Vec<PendingRateUpdate>,
+}
+
+#[cw_serde]
+pub struct RateMaxAgeEntry {
+    pub denom: String,
+    pub max_age: u64,
+}
+
+#[cw_serde]
+pub struct RateMaxAgesResponse {
+    pub max_ages: Vec<RateMaxAgeEntry>,
+}
+
+#[cw_serde]
+pub struct InsertPolicyEntry {
+    pub key: String,
+    pub policy: InsertPolicy,
+}
+
+#[cw_serde]
+pub struct InsertPoliciesResponse {
+    pub policies: Vec<InsertPolicyEntry>,
+}
+
+#[cw_serde]
+pub struct FreezeStatusResponse {
+    pub paused: bool,
+    pub frozen_keys: Vec<String>,
+    pub frozen_denoms: Vec<String>,
+}
+
+/// The status of a metric key relative to its heartbeat interval
+///  - Ok: the key was updated within the interval
+///  - Late: the key was not updated within the interval, but was updated within twice the interval
+///  - Stale: the key has not been updated within twice the interval (or has never been updated)
+///  - Unmonitored: no heartbeat interval has been configured for the key
+#[cw_serde]
+pub enum HealthStatus {
+    Ok,
+    Late,
+    Stale,
+    Unmonitored,
+}
+
+#[cw_serde]
+pub struct KeyHealth {
+    pub key: String,
+    pub last_update_time: Option<u64>,
+    pub seconds_since_update: Option<u64>,
+    pub heartbeat_interval: Option<u64>,
+    pub status: HealthStatus,
+}
+
+#[cw_serde]
+pub struct HealthResponse {
+    pub keys: Vec<KeyHealth>,
 }
 
 #[cw_serde]
@@ -109,16 +753,43 @@ pub struct Metrics {
     pub metrics: Vec<Metric>,
 }
 
+/// Summary statistics over a metric key's history
+///  - count is the number of values included (values that aren't numeric are skipped)
+///  - start_time and end_time are the update times of the oldest and newest values included
+///  - std_dev is the population standard deviation
+#[cw_serde]
+pub struct MetricStatsResponse {
+    pub count: u64,
+    pub start_time: u64,
+    pub end_time: u64,
+    pub min: Decimal,
+    pub max: Decimal,
+    pub mean: Decimal,
+    pub median: Decimal,
+    pub std_dev: Decimal,
+}
+
+#[cw_serde]
+pub struct EmaResponse {
+    pub value: Decimal,
+    pub update_time: u64,
+    pub half_life: u64,
+}
+
 #[cw_serde]
 pub struct RedemptionRateResponse {
     pub redemption_rate: Decimal,
     pub update_time: u64,
+    pub received_time: Option<u64>,
+    pub received_height: Option<u64>,
 }
 
 #[cw_serde]
 pub struct PurchaseRateResponse {
     pub purchase_rate: Decimal,
     pub update_time: u64,
+    pub received_time: Option<u64>,
+    pub received_height: Option<u64>,
 }
 
 #[cw_serde]
@@ -131,5 +802,10 @@ pub struct PurchaseRates {
     pub purchase_rates: Vec<PurchaseRate>,
 }
 
+#[cw_serde]
+pub struct SlashEvents {
+    pub slash_events: Vec<SlashEvent>,
+}
+
 #[cw_serde]
 pub struct MigrateMsg {}
@@ -1,6 +1,290 @@
-use crate::state::{Metric, METRICS, REDEMPTION_RATES, PURCHASE_RATES};
-use crate::msg::{Metrics, RedemptionRateResponse, RedemptionRates, PurchaseRateResponse, PurchaseRates};
-use cosmwasm_std::{Binary, Deps, Order, StdError, StdResult};
+use crate::error::ContractError;
+use crate::execute;
+use crate::helpers::{ensure_denom_not_frozen, linear_interpolation, time_weighted_average};
+use crate::state::{
+    BoundsTarget, Ema, HasTime, History, Metric, Rate, CONFIG, DENOM_BOUNDS, EMAS, EMA_HALF_LIVES,
+    FROZEN_DENOMS, FROZEN_KEYS, HEARTBEATS, INSERT_POLICIES, KEY_BOUNDS, METRICS, MONOTONIC_DENOMS,
+    PAUSED, PENDING_CHANGES, PENDING_RATE_UPDATES, POSTERS, POSTER_SCOPES, PURCHASE_RATES,
+    PURCHASE_RATE_EMAS, RATE_DEVIATION_LIMITS, RATE_MAX_AGES, REDEMPTION_RATES,
+    REDEMPTION_RATE_EMAS, SLASH_EVENTS,
+};
+use crate::msg::{
+    EmaResponse, FreezeStatusResponse, HealthResponse, HealthStatus, HistoryOrder,
+    InsertPoliciesResponse, InsertPolicyEntry, KeyHealth, MetricBoundsEntry, MetricBoundsResponse,
+    MetricStatsResponse, MetricUpdate, Metrics, MonotonicDenomsResponse, PendingChangesResponse,
+    PendingUpdatesResponse, PosterScopeEntry, PosterScopesResponse, PurchaseRateResponse,
+    PurchaseRates, RateDeviationLimitEntry, RateDeviationLimitsResponse, RateMaxAgeEntry,
+    RateMaxAgesResponse, RateQueryParams, RedemptionRateResponse, RedemptionRates, RolesResponse,
+    SimulatePostMetricResponse, SlashEvents,
+};
+use cosmwasm_std::{
+    ensure, from_binary, Addr, Binary, Decimal, Deps, Env, Order, StdError, StdResult,
+};
+use cw_storage_plus::{Bound, Map};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+const DEFAULT_PAGE_LIMIT: u32 = 10;
+const MAX_PAGE_LIMIT: u32 = 30;
+const MAX_HISTORY_PAGE_LIMIT: u64 = 50;
+
+/// Pagination options shared by the historical queries
+pub struct HistoryPage {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub start_after: Option<u64>,
+    pub order: Option<HistoryOrder>,
+    pub limit: Option<u64>,
+}
+
+/// Returns the admin, guardian and the full set of posters
+pub fn get_roles(deps: Deps) -> StdResult<RolesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    let posters = POSTERS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RolesResponse {
+        admin_address: config.admin_address,
+        pending_admin_address: config.pending_admin_address,
+        guardian_address: config.guardian_address,
+        posters,
+    })
+}
+
+/// Returns the scope of each restricted poster, paginated by address
+pub fn get_poster_scopes(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PosterScopesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start_after = start_after.map(Addr::unchecked);
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let scopes = POSTER_SCOPES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(address, scope)| PosterScopeEntry { address, scope }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PosterScopesResponse { scopes })
+}
+
+/// Returns the config changes queued behind the timelock, paginated by ID
+pub fn get_pending_changes(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<PendingChangesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let changes = PENDING_CHANGES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(_, change)| change))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PendingChangesResponse { changes })
+}
+
+/// Returns the deviation limit configured for each denom, paginated by denom
+pub fn get_rate_deviation_limits(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RateDeviationLimitsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let limits = RATE_DEVIATION_LIMITS
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(denom, limit)| RateDeviationLimitEntry { denom, limit }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RateDeviationLimitsResponse { limits })
+}
+
+/// Returns the denoms with the monotonic redemption rate policy enabled, paginated by denom
+pub fn get_monotonic_denoms(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<MonotonicDenomsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let denoms = MONOTONIC_DENOMS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MonotonicDenomsResponse { denoms })
+}
+
+/// Returns the hard bounds configured for each metric key, followed by each denom
+pub fn get_metric_bounds(deps: Deps) -> StdResult<MetricBoundsResponse> {
+    let key_bounds = KEY_BOUNDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| {
+            r.map(|(key, bounds)| MetricBoundsEntry {
+                target: BoundsTarget::Key(key),
+                bounds,
+            })
+        });
+    let denom_bounds = DENOM_BOUNDS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|r| {
+            r.map(|(denom, bounds)| MetricBoundsEntry {
+                target: BoundsTarget::Denom(denom),
+                bounds,
+            })
+        });
+    let bounds = key_bounds
+        .chain(denom_bounds)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(MetricBoundsResponse { bounds })
+}
+
+/// Returns the rate updates held by the circuit breaker, paginated by denom
+pub fn get_pending_updates(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<PendingUpdatesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let updates = PENDING_RATE_UPDATES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(_, update)| update))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(PendingUpdatesResponse { updates })
+}
+
+/// Returns the default max age configured for each denom's rates, paginated by denom
+pub fn get_rate_max_ages(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<RateMaxAgesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let max_ages = RATE_MAX_AGES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(denom, max_age)| RateMaxAgeEntry { denom, max_age }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(RateMaxAgesResponse { max_ages })
+}
+
+/// Returns the insert policy configured for each metric key, paginated by key
+pub fn get_insert_policies(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<InsertPoliciesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).min(MAX_PAGE_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let policies = INSERT_POLICIES
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|r| r.map(|(key, policy)| InsertPolicyEntry { key, policy }))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(InsertPoliciesResponse { policies })
+}
+
+/// Returns whether the contract is paused, along with all frozen keys and denoms
+pub fn get_freeze_status(deps: Deps) -> StdResult<FreezeStatusResponse> {
+    let paused = PAUSED.may_load(deps.storage)?.unwrap_or_default();
+    let frozen_keys = FROZEN_KEYS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let frozen_denoms = FROZEN_DENOMS
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(FreezeStatusResponse {
+        paused,
+        frozen_keys,
+        frozen_denoms,
+    })
+}
+
+/// Returns the health of each metric key, including keys with a heartbeat that have never been posted
+/// A key's status is determined by the seconds elapsed between its last update and the current block time
+pub fn get_health(deps: Deps, env: Env) -> StdResult<HealthResponse> {
+    let current_time = env.block.time.seconds();
+
+    // Collect the last update time of each key, and the keys that are expected to be posted
+    let mut last_update_times: BTreeMap<String, Option<u64>> = BTreeMap::new();
+    for entry in METRICS.range(deps.storage, None, None, Order::Ascending) {
+        let (key, history) = entry?;
+        let last_update_time = history.get_latest().map(|metric| metric.update_time);
+        last_update_times.insert(key, last_update_time);
+    }
+    for key in HEARTBEATS.keys(deps.storage, None, None, Order::Ascending) {
+        last_update_times.entry(key?).or_insert(None);
+    }
+
+    let keys = last_update_times
+        .into_iter()
+        .map(|(key, last_update_time)| {
+            let heartbeat_interval = HEARTBEATS.may_load(deps.storage, &key)?;
+            let seconds_since_update =
+                last_update_time.map(|update_time| current_time.saturating_sub(update_time));
+            let status = match (heartbeat_interval, seconds_since_update) {
+                (None, _) => HealthStatus::Unmonitored,
+                (Some(interval), Some(elapsed)) if elapsed <= interval => HealthStatus::Ok,
+                (Some(interval), Some(elapsed)) if elapsed <= interval.saturating_mul(2) => {
+                    HealthStatus::Late
+                }
+                (Some(_), _) => HealthStatus::Stale,
+            };
+
+            Ok(KeyHealth {
+                key,
+                last_update_time,
+                seconds_since_update,
+                heartbeat_interval,
+                status,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(HealthResponse { keys })
+}
+
+/// Simulates a metric post from the given sender, without storing the metric
+/// Validation failures are returned in the response rather than as a query error
+pub fn simulate_post_metric(
+    deps: Deps,
+    env: Env,
+    sender: String,
+    metric: MetricUpdate,
+) -> StdResult<SimulatePostMetricResponse> {
+    let sender = deps.api.addr_validate(&sender)?;
+    let response = match execute::simulate_post_metric(deps, &env, &sender, metric) {
+        Ok(outcome) => SimulatePostMetricResponse {
+            outcome: Some(outcome),
+            error: None,
+        },
+        Err(err) => SimulatePostMetricResponse {
+            outcome: None,
+            error: Some(err.to_string()),
+        },
+    };
+    Ok(response)
+}
 
 /// Returns the most up-to-date metric for all metrics stored
 pub fn get_all_latest_metrics(deps: Deps) -> StdResult<Metrics> {
@@ -21,48 +305,156 @@ pub fn get_latest_metric(deps: Deps, key: String) -> StdResult<Metric> {
     }
 }
 
-/// Returns the full history of a given metric, sorted by the time at which it was updated
-pub fn get_historical_metrics(deps: Deps, key: String, limit: Option<u64>) -> StdResult<Metrics> {
-    let metrics_history = METRICS.load(deps.storage, &key)?;
-    let metrics = match limit {
-        Some(limit) => metrics_history.get_latest_range(limit as usize),
-        None => metrics_history.get_all(),
+/// Returns the count, min, max, mean, median and standard deviation of a metric key's numeric
+/// values, within an optional time range
+pub fn get_metric_stats(
+    deps: Deps,
+    key: String,
+    since: Option<u64>,
+    until: Option<u64>,
+) -> Result<MetricStatsResponse, ContractError> {
+    let metrics_history = METRICS.may_load(deps.storage, &key)?.unwrap_or_default();
+    let in_range = |time: u64| {
+        !matches!(since, Some(since) if time < since)
+            && !matches!(until, Some(until) if time > until)
+    };
+    let samples: Vec<(u64, Decimal)> = metrics_history
+        .iter()
+        .filter(|metric| in_range(metric.update_time))
+        .filter_map(|metric| {
+            let value = Decimal::from_str(&metric.value).ok()?;
+            Some((metric.update_time, value))
+        })
+        .collect();
+
+    let (Some((start_time, _)), Some((end_time, _))) = (samples.first(), samples.last()) else {
+        return Err(ContractError::NoMetricValues { key });
     };
+    let count = samples.len() as u64;
+
+    let mut values: Vec<Decimal> = samples.iter().map(|(_, value)| *value).collect();
+    values.sort();
+
+    let mut sum = Decimal::zero();
+    for value in values.iter() {
+        sum = sum.checked_add(*value).map_err(StdError::from)?;
+    }
+    let mean = sum / Decimal::from_ratio(count, 1u64);
+
+    // With an even number of values, the median is the midpoint of the two middle values
+    let middle = values.len() / 2;
+    let median = match values.len() % 2 {
+        0 => {
+            let (lower, upper) = (values[middle - 1], values[middle]);
+            lower + (upper - lower) / Decimal::from_ratio(2u64, 1u64)
+        }
+        _ => values[middle],
+    };
+
+    let mut squared_deviations = Decimal::zero();
+    for value in values.iter() {
+        let deviation = value.abs_diff(mean);
+        squared_deviations = deviation
+            .checked_mul(deviation)
+            .and_then(|squared| squared_deviations.checked_add(squared))
+            .map_err(StdError::from)?;
+    }
+    let variance = squared_deviations / Decimal::from_ratio(count, 1u64);
+
+    Ok(MetricStatsResponse {
+        count,
+        start_time: *start_time,
+        end_time: *end_time,
+        min: values[0],
+        max: values[values.len() - 1],
+        mean,
+        median,
+        std_dev: variance.sqrt(),
+    })
+}
+
+/// Returns the moving average of a given metric key, along with its half-life
+pub fn get_ema(deps: Deps, key: String) -> StdResult<EmaResponse> {
+    let half_life = EMA_HALF_LIVES.load(deps.storage, &key)?;
+    let ema = EMAS.load(deps.storage, &key)?;
+    Ok(EmaResponse {
+        value: ema.value,
+        update_time: ema.update_time,
+        half_life,
+    })
+}
+
+/// Returns the metric in effect at the given time
+/// If interpolate is true, the metric's value is linearly interpolated with the following metric
+pub fn get_metric_at(
+    deps: Deps,
+    key: String,
+    time: u64,
+    interpolate: bool,
+) -> Result<Metric, ContractError> {
+    let metrics_history = METRICS.load(deps.storage, &key)?;
+    let (mut metric, next) = get_entries_at(&metrics_history, time)?;
+
+    if let Some(next) = next.filter(|_| interpolate) {
+        let value = linear_interpolation(
+            (metric.update_time, Decimal::from_str(&metric.value)?),
+            (next.update_time, Decimal::from_str(&next.value)?),
+            time,
+        );
+        metric.value = value.to_string();
+    }
+
+    Ok(metric)
+}
+
+/// Returns a page of the history of a given metric, sorted by the time at which it was updated
+pub fn get_historical_metrics(deps: Deps, key: String, page: HistoryPage) -> StdResult<Metrics> {
+    let metrics_history = METRICS.load(deps.storage, &key)?;
+    let metrics = get_history_page(&metrics_history, page);
     Ok(Metrics { metrics })
 }
 
 /// Returns the redemption rate of a given stToken and the time that it was last updated (used for price oracles)
+/// Fails if the rate is older than the max age passed in the params, or the denom's default max age
+/// If a TWAP window or the EMA option is passed in the params, the corresponding average is returned instead
 pub fn get_latest_redemption_rate(
     deps: Deps,
+    env: Env,
     denom: String,
     params: Option<Binary>,
-) -> StdResult<RedemptionRateResponse> {
-    // The params field of the redemption rate query should always be None
-    // It is included so that the query is at parity with other price oracles that
-    // may use it for things like TWAP
-    if params.is_some() {
-        return Err(StdError::generic_err(
-            "invalid query request - params must be None",
-        ));
-    }
+) -> Result<RedemptionRateResponse, ContractError> {
+    let params = parse_rate_query_params(params)?;
+
+    // Fail closed if the contract is paused or the denom has been frozen
+    ensure_denom_not_frozen(deps.storage, &denom)?;
 
     let redemption_rates_history = REDEMPTION_RATES.load(deps.storage, &denom)?;
 
-    match redemption_rates_history.get_latest() {
-        Some(response) => Ok(RedemptionRateResponse {
-            redemption_rate: response.redemption_rate,
-            update_time: response.update_time,
-        }),
-        None => Err(StdError::generic_err("redemption rate not found")),
-    }
+    let Some(response) = redemption_rates_history.get_latest() else {
+        return Err(StdError::generic_err("redemption rate not found").into());
+    };
+    ensure_rate_not_stale(deps, &env, &denom, response.update_time, params.max_age)?;
+
+    let redemption_rate = match (params.twap_window, params.ema) {
+        (Some(window), _) => get_twap(&env, &denom, &redemption_rates_history, window)?,
+        (None, true) => get_rate_ema(deps, &REDEMPTION_RATE_EMAS, &denom, response.update_time)?,
+        (None, false) => response.redemption_rate,
+    };
+
+    Ok(RedemptionRateResponse {
+        redemption_rate,
+        update_time: response.update_time,
+        received_time: response.received_time,
+        received_height: response.received_height,
+    })
 }
 
-/// Returns the full redemption rate history of an stToken, sorted by the time at which it was updated
+/// Returns a page of the redemption rate history of an stToken, sorted by the time at which it was updated
 pub fn get_historical_redemption_rates(
     deps: Deps,
     denom: String,
     params: Option<Binary>,
-    limit: Option<u64>,
+    page: HistoryPage,
 ) -> StdResult<RedemptionRates> {
     // The params field of the redemption rate query should always be None
     // It is included so that the query is at parity with other price oracles that
@@ -75,45 +467,79 @@ pub fn get_historical_redemption_rates(
 
     let redemption_rates_history = REDEMPTION_RATES.load(deps.storage, &denom)?;
 
-    let redemption_rates = match limit {
-        Some(limit) => redemption_rates_history.get_latest_range(limit as usize),
-        None => redemption_rates_history.get_all(),
-    };
+    let redemption_rates = get_history_page(&redemption_rates_history, page);
     Ok(RedemptionRates { redemption_rates })
 }
 
+/// Returns the redemption rate of a given stToken in effect at the given time
+/// If interpolate is true, the rate is linearly interpolated with the following rate
+pub fn get_redemption_rate_at(
+    deps: Deps,
+    denom: String,
+    time: u64,
+    interpolate: bool,
+) -> Result<RedemptionRateResponse, ContractError> {
+    let redemption_rates_history = REDEMPTION_RATES.load(deps.storage, &denom)?;
+    let (response, next) = get_entries_at(&redemption_rates_history, time)?;
+
+    let redemption_rate = match next.filter(|_| interpolate) {
+        Some(next) => linear_interpolation(
+            (response.update_time, response.redemption_rate),
+            (next.update_time, next.redemption_rate),
+            time,
+        ),
+        None => response.redemption_rate,
+    };
+
+    Ok(RedemptionRateResponse {
+        redemption_rate,
+        update_time: response.update_time,
+        received_time: response.received_time,
+        received_height: response.received_height,
+    })
+}
+
 /// Returns the purchase rate of a given stToken and the time that it was last updated (used for price oracles)
+/// Fails if the rate is older than the max age passed in the params, or the denom's default max age
+/// If a TWAP window or the EMA option is passed in the params, the corresponding average is returned instead
 pub fn get_latest_purchase_rate(
     deps: Deps,
+    env: Env,
     denom: String,
     params: Option<Binary>,
-) -> StdResult<PurchaseRateResponse> {
-    // The params field of the purchase rate query should always be None
-    // It is included so that the query is at parity with other price oracles that
-    // may use it for things like TWAP
-    if params.is_some() {
-        return Err(StdError::generic_err(
-            "invalid query request - params must be None",
-        ));
-    }
+) -> Result<PurchaseRateResponse, ContractError> {
+    let params = parse_rate_query_params(params)?;
+
+    // Fail closed if the contract is paused or the denom has been frozen
+    ensure_denom_not_frozen(deps.storage, &denom)?;
 
     let purchase_rates_history = PURCHASE_RATES.load(deps.storage, &denom)?;
 
-    match purchase_rates_history.get_latest() {
-        Some(response) => Ok(PurchaseRateResponse {
-            purchase_rate: response.purchase_rate,
-            update_time: response.update_time,
-        }),
-        None => Err(StdError::generic_err("purchase rate not found")),
-    }
+    let Some(response) = purchase_rates_history.get_latest() else {
+        return Err(StdError::generic_err("purchase rate not found").into());
+    };
+    ensure_rate_not_stale(deps, &env, &denom, response.update_time, params.max_age)?;
+
+    let purchase_rate = match (params.twap_window, params.ema) {
+        (Some(window), _) => get_twap(&env, &denom, &purchase_rates_history, window)?,
+        (None, true) => get_rate_ema(deps, &PURCHASE_RATE_EMAS, &denom, response.update_time)?,
+        (None, false) => response.purchase_rate,
+    };
+
+    Ok(PurchaseRateResponse {
+        purchase_rate,
+        update_time: response.update_time,
+        received_time: response.received_time,
+        received_height: response.received_height,
+    })
 }
 
-/// Returns the full purchase rate history of an milkTia, sorted by the time at which it was updated
+/// Returns a page of the purchase rate history of an milkTia, sorted by the time at which it was updated
 pub fn get_historical_purchase_rates(
     deps: Deps,
     denom: String,
     params: Option<Binary>,
-    limit: Option<u64>,
+    page: HistoryPage,
 ) -> StdResult<PurchaseRates> {
     // The params field of the redemption rate query should always be None
     // It is included so that the query is at parity with other price oracles that
@@ -126,9 +552,185 @@ pub fn get_historical_purchase_rates(
 
     let purchase_rates_history = PURCHASE_RATES.load(deps.storage, &denom)?;
 
-    let purchase_rates = match limit {
-        Some(limit) => purchase_rates_history.get_latest_range(limit as usize),
-        None => purchase_rates_history.get_all(),
-    };
+    let purchase_rates = get_history_page(&purchase_rates_history, page);
     Ok(PurchaseRates { purchase_rates })
 }
+
+/// Returns the purchase rate of a given milkTia in effect at the given time
+/// If interpolate is true, the rate is linearly interpolated with the following rate
+pub fn get_purchase_rate_at(
+    deps: Deps,
+    denom: String,
+    time: u64,
+    interpolate: bool,
+) -> Result<PurchaseRateResponse, ContractError> {
+    let purchase_rates_history = PURCHASE_RATES.load(deps.storage, &denom)?;
+    let (response, next) = get_entries_at(&purchase_rates_history, time)?;
+
+    let purchase_rate = match next.filter(|_| interpolate) {
+        Some(next) => linear_interpolation(
+            (response.update_time, response.purchase_rate),
+            (next.update_time, next.purchase_rate),
+            time,
+        ),
+        None => response.purchase_rate,
+    };
+
+    Ok(PurchaseRateResponse {
+        purchase_rate,
+        update_time: response.update_time,
+        received_time: response.received_time,
+        received_height: response.received_height,
+    })
+}
+
+/// Returns a page of the history of slash events, filtered and ordered by update time
+/// Returns an empty list if the stToken has never been slashed
+pub fn get_slash_events(deps: Deps, denom: String, page: HistoryPage) -> StdResult<SlashEvents> {
+    let limit = page
+        .limit
+        .unwrap_or(MAX_HISTORY_PAGE_LIMIT)
+        .min(MAX_HISTORY_PAGE_LIMIT) as usize;
+    let order: Order = page.order.unwrap_or_default().into();
+
+    // The cursor replaces the time filter on the side that the page continues from,
+    // unless the filter is already past the cursor
+    let mut min = page.start_time.map(Bound::inclusive);
+    let mut max = page.end_time.map(Bound::inclusive);
+    match (page.start_after, order) {
+        (Some(start_after), Order::Ascending) if !matches!(page.start_time, Some(start_time) if start_time > start_after) => {
+            min = Some(Bound::exclusive(start_after))
+        }
+        (Some(start_after), Order::Descending) if !matches!(page.end_time, Some(end_time) if end_time < start_after) => {
+            max = Some(Bound::exclusive(start_after))
+        }
+        _ => {}
+    }
+
+    let slash_events = SLASH_EVENTS
+        .prefix(&denom)
+        .range(deps.storage, min, max, order)
+        .take(limit)
+        .map(|r| r.map(|(_, slash_event)| slash_event))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SlashEvents { slash_events })
+}
+
+// Decodes the params of a rate query
+// Params are optional, and an empty object is equivalent to passing no params
+fn parse_rate_query_params(params: Option<Binary>) -> Result<RateQueryParams, ContractError> {
+    let params: RateQueryParams = match params {
+        Some(params) => from_binary(&params).map_err(|_| {
+            StdError::generic_err("invalid query request - params could not be decoded")
+        })?,
+        None => RateQueryParams::default(),
+    };
+    ensure!(
+        params.twap_window.is_none() || !params.ema,
+        StdError::generic_err("invalid query request - twap_window and ema cannot both be set")
+    );
+    Ok(params)
+}
+
+// Confirms a rate was updated within the max age, relative to the current block time
+// If the query did not specify a max age, the denom's default max age is used (if configured)
+fn ensure_rate_not_stale(
+    deps: Deps,
+    env: &Env,
+    denom: &str,
+    update_time: u64,
+    max_age: Option<u64>,
+) -> Result<(), ContractError> {
+    let max_age = match max_age {
+        Some(max_age) => Some(max_age),
+        None => RATE_MAX_AGES.may_load(deps.storage, denom)?,
+    };
+    if let Some(max_age) = max_age {
+        let current_time = env.block.time.seconds();
+        ensure!(
+            current_time.saturating_sub(update_time) <= max_age,
+            ContractError::StaleRate {
+                denom: denom.to_string(),
+                update_time,
+                current_time,
+                max_age,
+            }
+        );
+    }
+    Ok(())
+}
+
+// Returns the time-weighted average of a rate over the window ending at the current block time
+// Fails if the window starts before the oldest rate in the history
+fn get_twap<T: Rate>(
+    env: &Env,
+    denom: &str,
+    history: &History<T>,
+    window: u64,
+) -> Result<Decimal, ContractError> {
+    ensure!(window > 0, ContractError::InvalidTwapWindow {});
+
+    let end = env.block.time.seconds();
+    let start = end.saturating_sub(window);
+    time_weighted_average(history, start, end).ok_or_else(|| {
+        ContractError::TwapWindowExceedsHistory {
+            denom: denom.to_string(),
+            window,
+            oldest_update_time: history.iter().next().map_or(0, |oldest| oldest.time()),
+        }
+    })
+}
+
+// Returns the moving average of a denom's rate
+// Fails if the average does not include the latest rate (e.g. if the key's moving average
+// was removed after the rate was posted)
+fn get_rate_ema(
+    deps: Deps,
+    store: &Map<&str, Ema>,
+    denom: &str,
+    latest_update_time: u64,
+) -> Result<Decimal, ContractError> {
+    store
+        .may_load(deps.storage, denom)?
+        .filter(|ema| ema.update_time == latest_update_time)
+        .map(|ema| ema.value)
+        .ok_or_else(|| ContractError::EmaUnavailable {
+            denom: denom.to_string(),
+        })
+}
+
+// Returns the entry in effect at the given time (the latest entry at or before the time),
+// along with the entry that follows it, if there is one
+// Fails if the time predates the oldest entry in the history
+fn get_entries_at<T: HasTime + Clone>(
+    history: &History<T>,
+    time: u64,
+) -> Result<(T, Option<T>), ContractError> {
+    match history.get_at_or_before(time) {
+        Some(entry) => Ok((entry, history.get_after(time))),
+        None => match history.iter().next() {
+            Some(oldest) => Err(ContractError::TimePredatesHistory {
+                time,
+                oldest_update_time: oldest.time(),
+            }),
+            None => Err(StdError::generic_err("history is empty").into()),
+        },
+    }
+}
+
+// Returns the entries of a history that fall within the given page
+// The limit defaults to, and is capped at, the max page size
+fn get_history_page<T: HasTime + Clone>(history: &History<T>, page: HistoryPage) -> Vec<T> {
+    let limit = page
+        .limit
+        .unwrap_or(MAX_HISTORY_PAGE_LIMIT)
+        .min(MAX_HISTORY_PAGE_LIMIT) as usize;
+    let order = page.order.unwrap_or_default().into();
+    history.get_range(
+        page.start_time,
+        page.end_time,
+        page.start_after,
+        order,
+        limit,
+    )
+}
@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Binary, Decimal};
-use std::{collections::VecDeque, fmt, usize};
+use cosmwasm_std::{Addr, Binary, Decimal, Empty, Order};
+use std::{collections::VecDeque, fmt};
 
 use cw_storage_plus::{Item, Map};
 
@@ -8,15 +8,53 @@ use cw_storage_plus::{Item, Map};
 /// transfer chanel ID
 #[cw_serde]
 pub struct Config {
-    /// The admin address will be the ICA address for the account that's
+    /// The admin address is the contract owner, which manages the config and roles
+    /// It will typically be the ICA address for the account that's
     ///  owned by the source chain and lives on the contract chain
     pub admin_address: Addr,
+    /// The address that has been proposed as the new admin, if a handover is in progress
+    /// The handover only completes once this address accepts the role
+    pub pending_admin_address: Option<Addr>,
+    /// Optional guardian address, which is only permitted to pause the contract
+    pub guardian_address: Option<Addr>,
+    /// Optional delay (in seconds) applied to config and role changes
+    /// If set, changes are queued and can only be executed once the delay has passed
+    pub timelock_seconds: Option<u64>,
+    /// Optional limit (in seconds) on how far ahead of the current block time a
+    /// metric's update time can be
+    pub max_clock_skew: Option<u64>,
+    /// Optional limit (in seconds) on how far behind the current block time a
+    /// newly posted metric's update time can be
+    pub max_metric_age: Option<u64>,
+    /// Optional limit on the number of metrics that can be posted in a single batch
+    /// If not set, the default limit is applied
+    pub max_batch_size: Option<u64>,
     /// The transfer channel ID from the Oracle chain to the Controller chain,
     /// as defined on the Oracle chain
     /// This field is only necessary for redemption rate metrics and queries
     pub transfer_channel_id: Option<String>,
 }
 
+/// Roles that can be granted or revoked by the admin
+///  - Posters are permitted to post metrics
+///  - The guardian is permitted to pause the contract, freeze keys and denoms, cancel queued
+///    config changes, and approve or reject rate updates held by the circuit breaker
+///    (there can only be one guardian)
+#[cw_serde]
+pub enum Role {
+    Poster,
+    Guardian,
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Role::Poster => write!(f, "poster"),
+            Role::Guardian => write!(f, "guardian"),
+        }
+    }
+}
+
 /// This contract represents a generic key value store
 /// A "metric" is the term for a piece of information stored
 /// Each metric has a higher level category that helps inform if any other,
@@ -40,12 +78,198 @@ impl fmt::Display for MetricType {
     }
 }
 
+/// A change to the config or roles that is subject to the timelock
+#[cw_serde]
+pub enum ConfigChange {
+    UpdateConfig {
+        transfer_channel_id: Option<String>,
+    },
+    ProposeAdmin {
+        admin_address: Addr,
+    },
+    GrantRole {
+        role: Role,
+        address: Addr,
+    },
+    RevokeRole {
+        role: Role,
+        address: Addr,
+    },
+    SetPosterScope {
+        address: Addr,
+        scope: PosterScope,
+    },
+    RemovePosterScope {
+        address: Addr,
+    },
+    SetTimelock {
+        timelock_seconds: Option<u64>,
+    },
+    SetRateDeviationLimit {
+        denom: String,
+        limit: Option<RateDeviationLimit>,
+    },
+    SetMonotonicRedemptionRate {
+        denom: String,
+        enabled: bool,
+    },
+    SetMetricBounds {
+        target: BoundsTarget,
+        bounds: Option<MetricBounds>,
+    },
+    SetRateMaxAge {
+        denom: String,
+        max_age: Option<u64>,
+    },
+    SetHeartbeat {
+        key: String,
+        interval: Option<u64>,
+    },
+    SetMetricTimeLimits {
+        max_clock_skew: Option<u64>,
+        max_metric_age: Option<u64>,
+    },
+    SetInsertPolicy {
+        key: String,
+        policy: Option<InsertPolicy>,
+    },
+    SetMaxBatchSize {
+        max_batch_size: Option<u64>,
+    },
+    SetEmaHalfLife {
+        key: String,
+        half_life: Option<u64>,
+    },
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigChange::UpdateConfig { .. } => write!(f, "update_config"),
+            ConfigChange::ProposeAdmin { .. } => write!(f, "propose_admin"),
+            ConfigChange::GrantRole { .. } => write!(f, "grant_role"),
+            ConfigChange::RevokeRole { .. } => write!(f, "revoke_role"),
+            ConfigChange::SetPosterScope { .. } => write!(f, "set_poster_scope"),
+            ConfigChange::RemovePosterScope { .. } => write!(f, "remove_poster_scope"),
+            ConfigChange::SetTimelock { .. } => write!(f, "set_timelock"),
+            ConfigChange::SetRateDeviationLimit { .. } => write!(f, "set_rate_deviation_limit"),
+            ConfigChange::SetMonotonicRedemptionRate { .. } => {
+                write!(f, "set_monotonic_redemption_rate")
+            }
+            ConfigChange::SetMetricBounds { .. } => write!(f, "set_metric_bounds"),
+            ConfigChange::SetRateMaxAge { .. } => write!(f, "set_rate_max_age"),
+            ConfigChange::SetHeartbeat { .. } => write!(f, "set_heartbeat"),
+            ConfigChange::SetMetricTimeLimits { .. } => write!(f, "set_metric_time_limits"),
+            ConfigChange::SetInsertPolicy { .. } => write!(f, "set_insert_policy"),
+            ConfigChange::SetMaxBatchSize { .. } => write!(f, "set_max_batch_size"),
+            ConfigChange::SetEmaHalfLife { .. } => write!(f, "set_ema_half_life"),
+        }
+    }
+}
+
+/// A config change that has been queued while the timelock is active
+///  - execute_after is the unix timestamp (in seconds) after which the change can be executed
+#[cw_serde]
+pub struct PendingChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub execute_after: u64,
+}
+
+/// Restricts which metrics a poster is permitted to post
+///  - key_prefixes: the metric key must start with one of the prefixes
+///  - metric_types: the metric type must be one of the listed types
+///  - denoms: the denom of a redemption or purchase rate must be one of the listed denoms
+/// An empty list places no restriction on that dimension
+#[cw_serde]
+pub struct PosterScope {
+    pub key_prefixes: Vec<String>,
+    pub metric_types: Vec<MetricType>,
+    #[serde(default)]
+    pub denoms: Vec<String>,
+}
+
+impl PosterScope {
+    // Checks whether a metric with the given key and type falls within the scope
+    pub fn permits(&self, key: &str, metric_type: &MetricType) -> bool {
+        let key_permitted = self.key_prefixes.is_empty()
+            || self
+                .key_prefixes
+                .iter()
+                .any(|prefix| key.starts_with(prefix.as_str()));
+        let metric_type_permitted =
+            self.metric_types.is_empty() || self.metric_types.contains(metric_type);
+
+        key_permitted && metric_type_permitted
+    }
+
+    // Checks whether a rate for the given denom falls within the scope
+    pub fn permits_denom(&self, denom: &str) -> bool {
+        self.denoms.is_empty() || self.denoms.iter().any(|permitted| permitted == denom)
+    }
+}
+
+/// Determines whether a metric can be inserted before the latest metric for its key
+///  - AllowBackfill: metrics can be inserted anywhere in the history
+///  - StrictlyIncreasing: metrics must be newer than the latest metric, by time
+///    and then by block height
+#[cw_serde]
+#[derive(Default)]
+pub enum InsertOrdering {
+    #[default]
+    AllowBackfill,
+    StrictlyIncreasing,
+}
+
+impl fmt::Display for InsertOrdering {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InsertOrdering::AllowBackfill => write!(f, "allow_backfill"),
+            InsertOrdering::StrictlyIncreasing => write!(f, "strictly_increasing"),
+        }
+    }
+}
+
+/// Determines how a metric with the same time and block height as an existing metric is handled
+///  - ReplaceOnEqual: the new metric replaces the existing metric
+///  - RejectConflicting: the post fails if the new metric differs from the existing metric
+///    (posting an identical metric is still permitted)
+#[cw_serde]
+#[derive(Default)]
+pub enum DuplicatePolicy {
+    #[default]
+    ReplaceOnEqual,
+    RejectConflicting,
+}
+
+impl fmt::Display for DuplicatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DuplicatePolicy::ReplaceOnEqual => write!(f, "replace_on_equal"),
+            DuplicatePolicy::RejectConflicting => write!(f, "reject_conflicting"),
+        }
+    }
+}
+
+/// The policy applied when a new metric is inserted into the history for its key
+/// Regardless of policy, if a metric with the same time but a greater block height
+/// already exists, the new metric is rejected
+#[cw_serde]
+#[derive(Default)]
+pub struct InsertPolicy {
+    pub ordering: InsertOrdering,
+    pub duplicates: DuplicatePolicy,
+}
+
 /// The Metric struct represents the base unit for the generic oracle key-value store
 ///  - key/value represent the main piece of data that is intended to be stored
 ///  - metric_type represents a high level category for the metric
 ///  - update_time is the time at which the value was updated on the source chain
 ///  - block_height is the height at which the value was updated on the source chain
 ///  - attributes field contains any additional context that's needed
+///  - received_time is the time at which the metric was received on the oracle chain
+///  - received_height is the height at which the metric was received on the oracle chain
+///    (the received fields are not set on metrics that were posted before they were introduced)
 #[cw_serde]
 pub struct Metric {
     pub key: String,
@@ -54,19 +278,29 @@ pub struct Metric {
     pub update_time: u64,
     pub block_height: u64,
     pub attributes: Option<Binary>,
+    pub received_time: Option<u64>,
+    pub received_height: Option<u64>,
 }
 
 impl HasTime for Metric {
     fn time(&self) -> u64 {
         self.update_time
     }
+
+    fn height(&self) -> u64 {
+        self.block_height
+    }
 }
 
 /// For use in price oracles, the RedemptionRate metric requires the stToken denom
 /// as it appears on the controller chain (e.g. `stuosmo`)
+/// The slash flag must be set for the redemption rate to decrease if the denom
+/// has the monotonic redemption rate policy enabled
 #[cw_serde]
 pub struct RedemptionRateAttributes {
     pub sttoken_denom: String,
+    #[serde(default)]
+    pub slash: bool,
 }
 
 /// For use in price oracles, the PurchaseRate metric requires the milkTia denom
@@ -85,6 +319,10 @@ pub struct RedemptionRate {
     pub redemption_rate: Decimal,
     /// The unix timestamp representing when the redemption rate was last updated
     pub update_time: u64,
+    /// The unix timestamp at which the redemption rate was received on the oracle chain
+    pub received_time: Option<u64>,
+    /// The block height at which the redemption rate was received on the oracle chain
+    pub received_height: Option<u64>,
 }
 
 impl HasTime for RedemptionRate {
@@ -93,6 +331,37 @@ impl HasTime for RedemptionRate {
     }
 }
 
+impl Rate for RedemptionRate {
+    fn denom(&self) -> &str {
+        &self.denom
+    }
+
+    fn rate(&self) -> Decimal {
+        self.redemption_rate
+    }
+}
+
+/// The SlashEvent struct records a decrease in the redemption rate of an stToken
+#[cw_serde]
+pub struct SlashEvent {
+    /// stToken denom as an IBC hash, as it appears on the oracle chain
+    pub denom: String,
+    /// The redemption rate prior to the slash
+    pub previous_redemption_rate: Decimal,
+    /// The redemption rate after the slash
+    pub redemption_rate: Decimal,
+    /// The unix timestamp of the redemption rate update that carried the slash
+    pub update_time: u64,
+    /// The block height of the redemption rate update that carried the slash
+    pub block_height: u64,
+}
+
+impl HasTime for SlashEvent {
+    fn time(&self) -> u64 {
+        self.update_time
+    }
+}
+
 /// The PurchaseRate struct represents the purchase rate of an milkTia
 #[cw_serde]
 pub struct PurchaseRate {
@@ -102,6 +371,10 @@ pub struct PurchaseRate {
     pub purchase_rate: Decimal,
     /// The unix timestamp representing when the purchase rate was last updated
     pub update_time: u64,
+    /// The unix timestamp at which the purchase rate was received on the oracle chain
+    pub received_time: Option<u64>,
+    /// The block height at which the purchase rate was received on the oracle chain
+    pub received_height: Option<u64>,
 }
 
 impl HasTime for PurchaseRate {
@@ -110,6 +383,175 @@ impl HasTime for PurchaseRate {
     }
 }
 
+impl Rate for PurchaseRate {
+    fn denom(&self) -> &str {
+        &self.denom
+    }
+
+    fn rate(&self) -> Decimal {
+        self.purchase_rate
+    }
+}
+
+/// Shared behavior of the redemption and purchase rate stores, which allows the same
+/// safety checks to be applied to both
+pub trait Rate: HasTime + Clone {
+    fn denom(&self) -> &str;
+    fn rate(&self) -> Decimal;
+}
+
+/// The action taken when a rate update exceeds the configured deviation limit
+///  - Reject: the post fails with an error
+///  - Quarantine: the metric is still recorded in the METRICS store, but the rate
+///    is not written to the redemption/purchase rate store
+///  - CircuitBreaker: the rate (and its metric) is held as a pending update, and the denom's
+///    rates can't be posted or queried until the guardian approves or rejects the update
+#[cw_serde]
+pub enum DeviationAction {
+    Reject,
+    Quarantine,
+    CircuitBreaker,
+}
+
+/// A redemption or purchase rate update
+#[cw_serde]
+pub enum RateUpdate {
+    RedemptionRate(RedemptionRate),
+    PurchaseRate(PurchaseRate),
+}
+
+impl RateUpdate {
+    // Returns the denom of the update
+    pub fn denom(&self) -> &str {
+        match self {
+            RateUpdate::RedemptionRate(redemption_rate) => redemption_rate.denom(),
+            RateUpdate::PurchaseRate(purchase_rate) => purchase_rate.denom(),
+        }
+    }
+
+    // Returns the rate value of the update
+    pub fn rate(&self) -> Decimal {
+        match self {
+            RateUpdate::RedemptionRate(redemption_rate) => redemption_rate.rate(),
+            RateUpdate::PurchaseRate(purchase_rate) => purchase_rate.rate(),
+        }
+    }
+
+    // Returns the update time of the update
+    pub fn update_time(&self) -> u64 {
+        match self {
+            RateUpdate::RedemptionRate(redemption_rate) => redemption_rate.time(),
+            RateUpdate::PurchaseRate(purchase_rate) => purchase_rate.time(),
+        }
+    }
+}
+
+impl From<RedemptionRate> for RateUpdate {
+    fn from(redemption_rate: RedemptionRate) -> Self {
+        RateUpdate::RedemptionRate(redemption_rate)
+    }
+}
+
+impl From<PurchaseRate> for RateUpdate {
+    fn from(purchase_rate: PurchaseRate) -> Self {
+        RateUpdate::PurchaseRate(purchase_rate)
+    }
+}
+
+/// The exponential moving average of a metric key's value, or of a denom's rate
+///  - update_time is the update time of the newest value included in the average
+///  - previous is the average before the newest value was included (without its own previous),
+///    so that the newest value can be replaced without rebuilding the average
+#[cw_serde]
+pub struct Ema {
+    pub value: Decimal,
+    pub update_time: u64,
+    pub previous: Option<Box<Ema>>,
+}
+
+/// A rate update that tripped the circuit breaker and is awaiting review by the guardian
+///  - metric is the posted metric, which is held out of the METRICS store until the update is
+///    approved, so that an anomalous value isn't served while it's under review
+///  - slash_event is set if the update was flagged as a slash, and is recorded if it's approved
+///  - previous_rate is the latest rate at the time the update was posted
+///  - max_deviation is the deviation limit that the update exceeded
+#[cw_serde]
+pub struct PendingRateUpdate {
+    pub denom: String,
+    pub metric: Metric,
+    pub update: RateUpdate,
+    pub previous_rate: Decimal,
+    pub max_deviation: Decimal,
+    pub slash_event: Option<SlashEvent>,
+}
+
+/// Limits how far a new redemption or purchase rate can move from the latest rate for the denom
+///  - max_relative_deviation is the maximum allowed |new - latest| / latest (e.g. 0.05 for 5%)
+///  - if scaling_period is set, the allowed deviation grows linearly with the time elapsed since
+///    the latest rate, in units of the scaling period (it is never less than max_relative_deviation)
+///  - action determines how updates that exceed the limit are handled
+#[cw_serde]
+pub struct RateDeviationLimit {
+    pub max_relative_deviation: Decimal,
+    pub scaling_period: Option<u64>,
+    pub action: DeviationAction,
+}
+
+impl RateDeviationLimit {
+    // Returns the maximum allowed relative deviation given the seconds elapsed since the latest rate
+    pub fn allowed_deviation(&self, elapsed_seconds: u64) -> Decimal {
+        match self.scaling_period {
+            Some(period) if period > 0 => {
+                let periods = Decimal::from_ratio(elapsed_seconds, period).max(Decimal::one());
+                self.max_relative_deviation.saturating_mul(periods)
+            }
+            _ => self.max_relative_deviation,
+        }
+    }
+}
+
+/// Identifies what a set of metric bounds applies to
+///  - Key: any metric posted under the key whose value parses as a decimal
+///  - Denom: the redemption and purchase rates of the denom
+#[cw_serde]
+pub enum BoundsTarget {
+    Key(String),
+    Denom(String),
+}
+
+impl fmt::Display for BoundsTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoundsTarget::Key(key) => write!(f, "key {key}"),
+            BoundsTarget::Denom(denom) => write!(f, "denom {denom}"),
+        }
+    }
+}
+
+/// Hard limits on the value of a metric (inclusive)
+/// An unset min or max places no restriction on that side
+#[cw_serde]
+pub struct MetricBounds {
+    pub min: Option<Decimal>,
+    pub max: Option<Decimal>,
+}
+
+impl MetricBounds {
+    // Checks whether the value falls within the bounds
+    pub fn contains(&self, value: Decimal) -> bool {
+        !matches!(self.min, Some(min) if value < min)
+            && !matches!(self.max, Some(max) if value > max)
+    }
+}
+
+impl fmt::Display for MetricBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let min = self.min.map_or("-inf".to_string(), |min| min.to_string());
+        let max = self.max.map_or("inf".to_string(), |max| max.to_string());
+        write!(f, "[{min}, {max}]")
+    }
+}
+
 /// The history of each metric is also stored in the contract to enable
 ///   historical queries or averaging/smoothing
 /// For each metric, the history is stored in a deque with a max capacity
@@ -120,6 +562,33 @@ impl HasTime for PurchaseRate {
 ///  elements from the front of the deque when the capacity has been reached
 pub trait HasTime {
     fn time(&self) -> u64;
+
+    // Used as a tiebreaker between items with the same time
+    fn height(&self) -> u64 {
+        0
+    }
+}
+
+/// The result of adding an item to a History deque
+///  - Inserted: the item was added as a new entry
+///  - Replaced: the item replaced an existing entry with the same time
+///  - Dropped: the item was not added, either because it was older than every entry in a
+///    deque that's at capacity, or because an entry with the same time has a greater height
+#[cw_serde]
+pub enum InsertOutcome {
+    Inserted,
+    Replaced,
+    Dropped,
+}
+
+impl fmt::Display for InsertOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InsertOutcome::Inserted => write!(f, "inserted"),
+            InsertOutcome::Replaced => write!(f, "replaced"),
+            InsertOutcome::Dropped => write!(f, "dropped"),
+        }
+    }
 }
 
 #[cw_serde]
@@ -152,28 +621,72 @@ impl<T: HasTime + Clone> History<T> {
     //   If a metric is found with the same timestamp
     //     -> that implies the metric is a duplciate
     //     -> binary_search_by_key will return Ok
-    //     -> replace the old metric with the new metric
+    //     -> replace the old metric with the new metric, unless the old metric
+    //        has a greater height (in which case the new metric is ignored)
     //   If the same timestamp is not found
     //      -> that implies this metric is new
     //      -> binary_search_by_key will return Err
     //      -> we insert the new metric to the list
     //
     // Old items are removed from the front of the deque when capacity is reached
-    pub fn add(&mut self, item: T) {
+    // If the new item would be the oldest item in a deque that's at capacity, it is dropped
+    pub fn add(&mut self, item: T) -> InsertOutcome {
         match self.deque.binary_search_by_key(&item.time(), |m| m.time()) {
             Ok(index) => {
-                self.deque.remove(index);
-                self.deque.insert(index, item)
+                if item.height() < self.deque[index].height() {
+                    return InsertOutcome::Dropped;
+                }
+                self.deque[index] = item;
+                InsertOutcome::Replaced
             }
             Err(index) => {
                 self.deque.insert(index, item);
                 if self.deque.len() > self.capacity as usize {
                     self.deque.pop_front();
+                    if index == 0 {
+                        return InsertOutcome::Dropped;
+                    }
                 }
+                InsertOutcome::Inserted
             }
         }
     }
 
+    // Removes the item with the given timestamp, returning it if it was found
+    pub fn remove(&mut self, time: u64) -> Option<T> {
+        let index = self.deque.binary_search_by_key(&time, |m| m.time()).ok()?;
+        self.deque.remove(index)
+    }
+
+    // Returns the item with the given timestamp, if there is one
+    pub fn get(&self, time: u64) -> Option<T> {
+        let index = self.deque.binary_search_by_key(&time, |m| m.time()).ok()?;
+        self.deque.get(index).cloned()
+    }
+
+    // Returns the latest item at or before the given timestamp, if there is one
+    pub fn get_at_or_before(&self, time: u64) -> Option<T> {
+        let index = match self.deque.binary_search_by_key(&time, |m| m.time()) {
+            Ok(index) => index,
+            Err(index) => index.checked_sub(1)?,
+        };
+        self.deque.get(index).cloned()
+    }
+
+    // Returns the earliest item after the given timestamp, if there is one
+    pub fn get_after(&self, time: u64) -> Option<T> {
+        let index = match self.deque.binary_search_by_key(&time, |m| m.time()) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        };
+        self.deque.get(index).cloned()
+    }
+
+    // Returns true if there are no items in the deque
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
     // Grabs the most recent item from the deque
     pub fn get_latest(&self) -> Option<T> {
         self.deque.back().cloned()
@@ -188,11 +701,129 @@ impl<T: HasTime + Clone> History<T> {
     pub fn get_all(&self) -> Vec<T> {
         self.deque.iter().rev().cloned().collect()
     }
+
+    // Returns up to `limit` items with a timestamp between start_time and end_time (inclusive),
+    // in the given order
+    // If start_after is provided, only items after it in the given order are returned
+    // (i.e. newer items when ascending, and older items when descending)
+    pub fn get_range(
+        &self,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        start_after: Option<u64>,
+        order: Order,
+        limit: usize,
+    ) -> Vec<T> {
+        let mut start = start_time.map_or(0, |t| self.deque.partition_point(|m| m.time() < t));
+        let mut end = end_time.map_or(self.deque.len(), |t| {
+            self.deque.partition_point(|m| m.time() <= t)
+        });
+
+        if let Some(cursor) = start_after {
+            match order {
+                Order::Ascending => {
+                    start = start.max(self.deque.partition_point(|m| m.time() <= cursor))
+                }
+                Order::Descending => {
+                    end = end.min(self.deque.partition_point(|m| m.time() < cursor))
+                }
+            }
+        }
+        if start >= end {
+            return vec![];
+        }
+
+        let items = self.deque.range(start..end);
+        match order {
+            Order::Ascending => items.take(limit).cloned().collect(),
+            Order::Descending => items.rev().take(limit).cloned().collect(),
+        }
+    }
+
+    // Returns an iterator over the items, from oldest to newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.deque.iter()
+    }
 }
 
 /// The CONFIG store stores contract configuration such as the admin address
 pub const CONFIG: Item<Config> = Item::new("config");
 
+/// The POSTERS store stores the set of addresses that are permitted to post metrics
+pub const POSTERS: Map<&Addr, Empty> = Map::new("posters");
+
+/// The POSTER_SCOPES store restricts the keys and metric types that a given poster can post
+/// Posters without an entry in this store can post any metric
+pub const POSTER_SCOPES: Map<&Addr, PosterScope> = Map::new("poster_scopes");
+
+/// The PENDING_CHANGES store contains config changes that are waiting on the timelock, key'd by ID
+pub const PENDING_CHANGES: Map<u64, PendingChange> = Map::new("pending_changes");
+
+/// The PENDING_CHANGE_ID store tracks the ID of the most recently queued config change
+pub const PENDING_CHANGE_ID: Item<u64> = Item::new("pending_change_id");
+
+/// The RATE_DEVIATION_LIMITS store contains the deviation limit applied to the redemption and
+///  purchase rates of each denom, key'd on the denom
+pub const RATE_DEVIATION_LIMITS: Map<&str, RateDeviationLimit> = Map::new("rate_deviation_limits");
+
+/// The MONOTONIC_DENOMS store contains the denoms whose redemption rate can only decrease
+///  if the update is flagged as a slash
+pub const MONOTONIC_DENOMS: Map<&str, Empty> = Map::new("monotonic_denoms");
+
+/// The KEY_BOUNDS store contains the hard bounds applied to the value of a metric key
+pub const KEY_BOUNDS: Map<&str, MetricBounds> = Map::new("key_bounds");
+
+/// The DENOM_BOUNDS store contains the hard bounds applied to the redemption and purchase
+///  rates of a denom
+pub const DENOM_BOUNDS: Map<&str, MetricBounds> = Map::new("denom_bounds");
+
+/// The PENDING_RATE_UPDATES store contains the rate updates held by the circuit breaker,
+///  key'd on the denom
+/// The denom's rates can't be posted while an update is pending, so there is at most one per denom
+/// This is tracked separately from FROZEN_DENOMS, so that reviewing an update never lifts a freeze
+pub const PENDING_RATE_UPDATES: Map<&str, PendingRateUpdate> = Map::new("pending_rate_updates");
+
+/// The RATE_MAX_AGES store contains the default max age (in seconds) of each denom's rates
+/// Rate queries that don't specify a max age fail if the latest rate is older than this
+pub const RATE_MAX_AGES: Map<&str, u64> = Map::new("rate_max_ages");
+
+/// The HEARTBEATS store contains the expected interval (in seconds) between updates of a metric key
+/// It is used by the Health query to flag keys that have stopped being posted
+pub const HEARTBEATS: Map<&str, u64> = Map::new("heartbeats");
+
+/// The INSERT_POLICIES store contains the insert policy for each metric key
+/// Keys without an entry use the default policy (allow backfill, replace on equal)
+pub const INSERT_POLICIES: Map<&str, InsertPolicy> = Map::new("insert_policies");
+
+/// The EMA_HALF_LIVES store contains the half-life (in seconds) of the moving average of each
+///  metric key
+/// Keys without an entry do not have a moving average
+pub const EMA_HALF_LIVES: Map<&str, u64> = Map::new("ema_half_lives");
+
+/// The EMAS store contains the moving average of each metric key, key'd on the metric key
+pub const EMAS: Map<&str, Ema> = Map::new("emas");
+
+/// The REDEMPTION_RATE_EMAS store contains the moving average of each stToken's redemption rate,
+///  key'd on the stToken denom
+/// It uses the half-life of the metric key that the redemption rates are posted under
+pub const REDEMPTION_RATE_EMAS: Map<&str, Ema> = Map::new("redemption_rate_emas");
+
+/// The PURCHASE_RATE_EMAS store contains the moving average of each stToken's purchase rate,
+///  key'd on the stToken denom
+/// It uses the half-life of the metric key that the purchase rates are posted under
+pub const PURCHASE_RATE_EMAS: Map<&str, Ema> = Map::new("purchase_rate_emas");
+
+/// The PAUSED store indicates whether the contract has been paused globally
+/// While paused, no metrics can be posted and the rate queries return an error
+pub const PAUSED: Item<bool> = Item::new("paused");
+
+/// The FROZEN_KEYS store contains the metric keys that can no longer be posted to
+pub const FROZEN_KEYS: Map<&str, Empty> = Map::new("frozen_keys");
+
+/// The FROZEN_DENOMS store contains the denoms whose redemption and purchase rates
+///  can no longer be posted to or queried
+pub const FROZEN_DENOMS: Map<&str, Empty> = Map::new("frozen_denoms");
+
 /// The METRICS store stores the full history of a metric
 /// It is key'd on the metric "key" field, but consists of a list (deque) of each metric sorted by update time
 pub const METRICS: Map<&str, History<Metric>> = Map::new("metrics");
@@ -205,26 +836,49 @@ pub const REDEMPTION_RATES: Map<&str, History<RedemptionRate>> = Map::new("redem
 /// It is key'd on the stToken denom, and consists of a list (deque) of each redemption rate sorted by time
 pub const PURCHASE_RATES: Map<&str, History<PurchaseRate>> = Map::new("purchase_rates");
 
+/// The SLASH_EVENTS store records each accepted decrease in an stToken's redemption rate
+/// It is key'd on the stToken denom and the update time of the slash
+/// Unlike the rate stores, it is not capped and is not pruned when metrics are removed,
+/// so that it serves as a complete audit trail
+pub const SLASH_EVENTS: Map<(&str, u64), SlashEvent> = Map::new("slash_events");
+
 #[cfg(test)]
 mod tests {
-    use crate::state::{HasTime, History};
+    use crate::state::{HasTime, History, InsertOutcome};
     use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::Order;
 
     // Test item used to test the History deque
     #[cw_serde]
     pub struct DummyItem {
         pub value: u64,
         pub update_time: u64,
+        pub block_height: u64,
     }
     impl DummyItem {
         pub fn new(value: u64, update_time: u64) -> Self {
-            DummyItem { value, update_time }
+            DummyItem {
+                value,
+                update_time,
+                block_height: 0,
+            }
+        }
+        pub fn new_with_height(value: u64, update_time: u64, block_height: u64) -> Self {
+            DummyItem {
+                value,
+                update_time,
+                block_height,
+            }
         }
     }
     impl HasTime for DummyItem {
         fn time(&self) -> u64 {
             self.update_time
         }
+
+        fn height(&self) -> u64 {
+            self.block_height
+        }
     }
 
     // Helper function to check the state/ordering of the deque
@@ -247,16 +901,19 @@ mod tests {
         check_deque_values(history.clone(), expected);
 
         // Add a new item that goes over capacity, it should kick out the oldest
-        history.add(DummyItem::new(600, 6)); // [(200,2) (500,3) (300,4) (400,5) (600,6)]
+        let outcome = history.add(DummyItem::new(600, 6)); // [(200,2) (500,3) (300,4) (400,5) (600,6)]
+        assert_eq!(outcome, InsertOutcome::Inserted);
         let expected = vec![200, 500, 300, 400, 600];
         check_deque_values(history.clone(), expected.clone());
 
         // Attempt to add an old item to the front, the deque should not change since it's at capacity
-        history.add(DummyItem::new(700, 0));
+        let outcome = history.add(DummyItem::new(700, 0));
+        assert_eq!(outcome, InsertOutcome::Dropped);
         check_deque_values(history.clone(), expected);
 
         // Replace the oldest item
-        history.add(DummyItem::new(800, 2)); // [(800,2) (500,3) (300,4) (400,5) (600,6)
+        let outcome = history.add(DummyItem::new(800, 2)); // [(800,2) (500,3) (300,4) (400,5) (600,6)
+        assert_eq!(outcome, InsertOutcome::Replaced);
         let expected = vec![800, 500, 300, 400, 600];
         check_deque_values(history.clone(), expected);
 
@@ -277,5 +934,128 @@ mod tests {
         let expected: Vec<u64> = vec![600, 400, 300, 500, 800]; // list is flipped cause newest items are first
         let actual: Vec<u64> = history.get_all().iter().map(|i| i.value).collect();
         assert_eq!(expected, actual);
+
+        // Remove an item from the middle, and attempt to remove an item that doesn't exist
+        assert_eq!(history.remove(4).unwrap().value, 300);
+        assert_eq!(history.remove(4), None);
+        let expected = vec![800, 500, 400, 600];
+        check_deque_values(history.clone(), expected);
+    }
+
+    #[test]
+    fn test_deque_height_tiebreaker() {
+        let mut history = History::<DummyItem>::new(5);
+        history.add(DummyItem::new_with_height(100, 1, 10));
+        history.add(DummyItem::new_with_height(200, 2, 20));
+
+        // An item with the same time but a lower height should be ignored
+        let outcome = history.add(DummyItem::new_with_height(300, 2, 19));
+        assert_eq!(outcome, InsertOutcome::Dropped);
+        check_deque_values(history.clone(), vec![100, 200]);
+
+        // An item with the same time and an equal or greater height should replace the existing item
+        history.add(DummyItem::new_with_height(400, 2, 20));
+        check_deque_values(history.clone(), vec![100, 400]);
+        history.add(DummyItem::new_with_height(500, 2, 21));
+        check_deque_values(history.clone(), vec![100, 500]);
+
+        // Test get
+        assert_eq!(history.get(2).unwrap().value, 500);
+        assert_eq!(history.get(3), None);
+    }
+
+    #[test]
+    fn test_deque_get_at_or_before() {
+        let mut history = History::<DummyItem>::new(5);
+        history.add(DummyItem::new(100, 10));
+        history.add(DummyItem::new(200, 20));
+        history.add(DummyItem::new(300, 30));
+
+        let value_at = |time| history.get_at_or_before(time).map(|item| item.value);
+        let value_after = |time| history.get_after(time).map(|item| item.value);
+
+        // Times before the oldest item have no item at or before them
+        assert_eq!(value_at(9), None);
+        assert_eq!(value_after(9), Some(100));
+
+        // Times that match an item return that item, followed by the next item
+        assert_eq!(value_at(20), Some(200));
+        assert_eq!(value_after(20), Some(300));
+
+        // Times between items return the item before, followed by the item after
+        assert_eq!(value_at(25), Some(200));
+        assert_eq!(value_after(25), Some(300));
+
+        // Times after the latest item return the latest item, with nothing after it
+        assert_eq!(value_at(31), Some(300));
+        assert_eq!(value_after(30), None);
+    }
+
+    #[test]
+    fn test_deque_get_range() {
+        let mut history = History::<DummyItem>::new(10);
+        for time in 1..=5 {
+            history.add(DummyItem::new(time * 100, time));
+        }
+
+        let values = |start_time, end_time, start_after, order, limit| {
+            history
+                .get_range(start_time, end_time, start_after, order, limit)
+                .into_iter()
+                .map(|item| item.value)
+                .collect::<Vec<_>>()
+        };
+
+        // Without any filters, all items are returned in the given order
+        assert_eq!(
+            values(None, None, None, Order::Ascending, 10),
+            vec![100, 200, 300, 400, 500]
+        );
+        assert_eq!(
+            values(None, None, None, Order::Descending, 10),
+            vec![500, 400, 300, 200, 100]
+        );
+
+        // The time range is inclusive, and the limit is taken from the start of the order
+        assert_eq!(
+            values(Some(2), Some(4), None, Order::Ascending, 10),
+            vec![200, 300, 400]
+        );
+        assert_eq!(
+            values(Some(2), Some(4), None, Order::Descending, 2),
+            vec![400, 300]
+        );
+
+        // The cursor excludes its own item, and everything before it in the given order
+        assert_eq!(
+            values(None, None, Some(2), Order::Ascending, 2),
+            vec![300, 400]
+        );
+        assert_eq!(
+            values(None, None, Some(4), Order::Descending, 2),
+            vec![300, 200]
+        );
+        assert_eq!(
+            values(Some(3), None, Some(1), Order::Ascending, 10),
+            vec![300, 400, 500]
+        );
+        assert_eq!(
+            values(None, Some(2), Some(4), Order::Descending, 10),
+            vec![200, 100]
+        );
+
+        // Empty ranges return no items
+        assert_eq!(
+            values(Some(4), Some(2), None, Order::Ascending, 10),
+            Vec::<u64>::new()
+        );
+        assert_eq!(
+            values(None, None, Some(5), Order::Ascending, 10),
+            Vec::<u64>::new()
+        );
+        assert_eq!(
+            values(None, None, Some(1), Order::Descending, 10),
+            Vec::<u64>::new()
+        );
     }
 }
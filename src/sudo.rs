@@ -0,0 +1,203 @@
+use crate::error::ContractError;
+use crate::helpers::{ema_from_values, validate_channel_id, validate_timelock};
+use crate::state::{
+    Ema, History, Metric, MetricType, PurchaseRateAttributes, Rate, RedemptionRateAttributes,
+    CONFIG, EMAS, EMA_HALF_LIVES, METRICS, PAUSED, PURCHASE_RATES, PURCHASE_RATE_EMAS,
+    REDEMPTION_RATES, REDEMPTION_RATE_EMAS,
+};
+use cosmwasm_std::{from_binary, Decimal, DepsMut, Response, Storage};
+use cw_storage_plus::Map;
+use std::str::FromStr;
+
+/// Replaces the admin address without requiring the admin's signature
+/// Any pending admin handover is cancelled
+pub fn update_admin(deps: DepsMut, admin_address: String) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.admin_address = deps.api.addr_validate(&admin_address)?;
+    config.pending_admin_address = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "sudo_update_admin")
+        .add_attribute("admin_address", admin_address))
+}
+
+/// Pauses the contract, blocking all metric posts and rate queries
+pub fn pause(deps: DepsMut) -> Result<Response, ContractError> {
+    PAUSED.save(deps.storage, &true)?;
+    Ok(Response::new().add_attribute("action", "sudo_pause"))
+}
+
+/// Unpauses the contract
+pub fn unpause(deps: DepsMut) -> Result<Response, ContractError> {
+    PAUSED.save(deps.storage, &false)?;
+    Ok(Response::new().add_attribute("action", "sudo_unpause"))
+}
+
+/// Removes either a single metric or the full history of a metric key
+/// Any redemption or purchase rates (and slash events) that were derived from the removed metrics are also removed
+/// Moving averages that included the removed metrics are rebuilt from the remaining history
+pub fn remove_metric(
+    deps: DepsMut,
+    key: String,
+    update_time: Option<u64>,
+) -> Result<Response, ContractError> {
+    let metric_not_found = || ContractError::MetricNotFound { key: key.clone() };
+    let mut metric_history = METRICS
+        .may_load(deps.storage, &key)?
+        .ok_or_else(metric_not_found)?;
+
+    let removed_metrics = match update_time {
+        Some(update_time) => {
+            let metric = metric_history
+                .remove(update_time)
+                .ok_or_else(metric_not_found)?;
+            vec![metric]
+        }
+        None => {
+            let metrics = metric_history.get_all();
+            metric_history = Default::default();
+            metrics
+        }
+    };
+
+    if metric_history.is_empty() {
+        METRICS.remove(deps.storage, &key);
+    } else {
+        METRICS.save(deps.storage, &key, &metric_history)?;
+    }
+
+    // Rebuild the key's moving average from the remaining history
+    let ema_half_life = EMA_HALF_LIVES.may_load(deps.storage, &key)?;
+    if let Some(half_life) = ema_half_life {
+        let values = metric_history.iter().filter_map(|metric| {
+            let value = Decimal::from_str(&metric.value).ok()?;
+            Some((metric.update_time, value))
+        });
+        save_ema(
+            deps.storage,
+            &EMAS,
+            &key,
+            ema_from_values(values, half_life),
+        )?;
+    }
+
+    for metric in removed_metrics.iter() {
+        remove_derived_rate(deps.storage, metric, ema_half_life)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "sudo_remove_metric")
+        .add_attribute("metric_key", key)
+        .add_attribute("removed_count", removed_metrics.len().to_string()))
+}
+
+/// Updates the transfer channel ID stored in the config
+pub fn update_config(
+    deps: DepsMut,
+    transfer_channel_id: Option<String>,
+) -> Result<Response, ContractError> {
+    if let Some(channel_id) = transfer_channel_id.as_ref() {
+        validate_channel_id(channel_id)?;
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.transfer_channel_id = transfer_channel_id;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "sudo_update_config")
+        .add_attribute(
+            "transfer_channel_id",
+            config
+                .transfer_channel_id
+                .unwrap_or_else(|| "None".to_string()),
+        ))
+}
+
+/// Sets or removes the timelock, bypassing any existing timelock
+pub fn set_timelock(
+    deps: DepsMut,
+    timelock_seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    validate_timelock(timelock_seconds)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    config.timelock_seconds = timelock_seconds;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "sudo_set_timelock")
+        .add_attribute(
+            "timelock_seconds",
+            timelock_seconds.map_or("None".to_string(), |t| t.to_string()),
+        ))
+}
+
+// Removes the redemption or purchase rate that was stored alongside the given metric
+// Any slash event recorded with the rate is kept, so that the slash history remains a full audit trail
+// If the metric's attributes can't be decoded, it never made it into the rate stores
+// If the metric's key has a moving average, the denom's rate average is rebuilt with the key's half-life
+fn remove_derived_rate(
+    storage: &mut dyn Storage,
+    metric: &Metric,
+    ema_half_life: Option<u64>,
+) -> Result<(), ContractError> {
+    let Some(attributes) = metric.attributes.as_ref() else {
+        return Ok(());
+    };
+
+    match metric.metric_type {
+        MetricType::RedemptionRate => {
+            let Ok(attributes) = from_binary::<RedemptionRateAttributes>(attributes) else {
+                return Ok(());
+            };
+            let denom = attributes.sttoken_denom;
+            if let Some(mut history) = REDEMPTION_RATES.may_load(storage, &denom)? {
+                history.remove(metric.update_time);
+                REDEMPTION_RATES.save(storage, &denom, &history)?;
+                if let Some(half_life) = ema_half_life {
+                    let ema = rate_ema_from_history(&history, half_life);
+                    save_ema(storage, &REDEMPTION_RATE_EMAS, &denom, ema)?;
+                }
+            }
+        }
+        MetricType::PurchaseRate => {
+            let Ok(attributes) = from_binary::<PurchaseRateAttributes>(attributes) else {
+                return Ok(());
+            };
+            let denom = attributes.sttoken_denom;
+            if let Some(mut history) = PURCHASE_RATES.may_load(storage, &denom)? {
+                history.remove(metric.update_time);
+                PURCHASE_RATES.save(storage, &denom, &history)?;
+                if let Some(half_life) = ema_half_life {
+                    let ema = rate_ema_from_history(&history, half_life);
+                    save_ema(storage, &PURCHASE_RATE_EMAS, &denom, ema)?;
+                }
+            }
+        }
+        MetricType::Other(_) => {}
+    }
+
+    Ok(())
+}
+
+// Builds the moving average of a denom's rate from its rate history
+fn rate_ema_from_history<T: Rate>(history: &History<T>, half_life: u64) -> Option<Ema> {
+    let values = history.iter().map(|rate| (rate.time(), rate.rate()));
+    ema_from_values(values, half_life)
+}
+
+// Saves a rebuilt moving average, or removes it if there are no values left to average
+fn save_ema(
+    storage: &mut dyn Storage,
+    store: &Map<&str, Ema>,
+    key: &str,
+    ema: Option<Ema>,
+) -> Result<(), ContractError> {
+    match ema {
+        Some(ema) => store.save(storage, key, &ema)?,
+        None => store.remove(storage, key),
+    }
+    Ok(())
+}